@@ -0,0 +1,86 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use sox::environment::StoreMode;
+use sox::init::{run, WarningMode};
+
+const FIB_SOURCE: &str = r#"
+def fib(n) {
+    if (n < 2) {
+        return n;
+    }
+    return fib(n - 1) + fib(n - 2);
+}
+
+let result = fib(15);
+"#;
+
+const CONDITION_SOURCE: &str = r#"
+let count = 0;
+for (let i = 0; i < 2000; i = i + 1) {
+    if (true and i) {
+        count = count + 1;
+    }
+}
+"#;
+
+const EQUALITY_SOURCE: &str = r#"
+let matches = 0;
+for (let i = 0; i < 2000; i = i + 1) {
+    if (i % 7 == 3) {
+        matches = matches + 1;
+    }
+    if ("a" != "b" and None == None) {
+        matches = matches + 1;
+    }
+}
+"#;
+
+const METHOD_SOURCE: &str = r#"
+class Base {
+    value() {
+        return 1;
+    }
+}
+class Derived: Base {}
+
+let d = Derived();
+let total = 0;
+for (let i = 0; i < 2000; i = i + 1) {
+    total = total + d.value();
+}
+"#;
+
+fn fib_benchmark(c: &mut Criterion) {
+    c.bench_function("fib 15", |b| {
+        b.iter(|| run(FIB_SOURCE.to_string(), true, StoreMode::Vec, WarningMode::Print).unwrap())
+    });
+    c.bench_function("fib 15 (map store)", |b| {
+        b.iter(|| run(FIB_SOURCE.to_string(), true, StoreMode::Map, WarningMode::Print).unwrap())
+    });
+}
+
+fn condition_benchmark(c: &mut Criterion) {
+    c.bench_function("if conditions 2000", |b| {
+        b.iter(|| run(CONDITION_SOURCE.to_string(), true, StoreMode::Vec, WarningMode::Print).unwrap())
+    });
+}
+
+fn equality_benchmark(c: &mut Criterion) {
+    c.bench_function("equality 2000", |b| {
+        b.iter(|| run(EQUALITY_SOURCE.to_string(), true, StoreMode::Vec, WarningMode::Print).unwrap())
+    });
+}
+
+fn method_benchmark(c: &mut Criterion) {
+    c.bench_function("inherited method calls 2000", |b| {
+        b.iter(|| run(METHOD_SOURCE.to_string(), true, StoreMode::Vec, WarningMode::Print).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    fib_benchmark,
+    condition_benchmark,
+    equality_benchmark,
+    method_benchmark
+);
+criterion_main!(benches);
@@ -1,9 +1,10 @@
 use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
 use quote::{quote, ToTokens};
-use syn::{ImplItem, Item};
+use syn::{Attribute, Ident, ImplItem, Item, LitStr, Meta};
 
 pub fn soxtype_impl(item: Item) -> TokenStream {
     let mut methods = Vec::new();
+    let mut static_methods = Vec::new();
 
     let tokens = match item.clone() {
         Item::Impl(item_impl) => {
@@ -17,7 +18,20 @@ pub fn soxtype_impl(item: Item) -> TokenStream {
 
                         for attr in v.attrs.iter() {
                             if attr.path().is_ident("soxmethod") {
-                                methods.push((fn_name.to_string(), fn_name.clone()));
+                                match method_options(attr) {
+                                    Ok(options) => {
+                                        let entry = (
+                                            options.name.unwrap_or_else(|| fn_name.to_string()),
+                                            fn_name.clone(),
+                                        );
+                                        if options.is_static {
+                                            static_methods.push(entry);
+                                        } else {
+                                            methods.push(entry);
+                                        }
+                                    }
+                                    Err(e) => return e.to_compile_error(),
+                                }
                             }
                         }
                     }
@@ -25,22 +39,14 @@ pub fn soxtype_impl(item: Item) -> TokenStream {
                     _ => {}
                 }
             }
-            let mut inner_tokens = TokenStream::new();
-            let mut tokens = TokenStream::new();
-            for (method_name, method) in methods {
-                inner_tokens.extend(quote! [
-                    (#method_name, SoxMethod{
-                        func: static_func(#ident::#method)
-                    }),
-                ]);
-            }
-            let array: TokenTree = Group::new(Delimiter::Bracket, inner_tokens).into();
-            tokens.extend([array]);
+            let tokens = method_defs(&ident, methods);
+            let static_tokens = method_defs(&ident, static_methods);
 
             quote! {
                 #item_impl
                 impl SoxClassImpl for #ident{
                     const METHOD_DEFS: &'static [(&'static str, SoxMethod)] = &#tokens;
+                    const STATIC_METHOD_DEFS: &'static [(&'static str, SoxMethod)] = &#static_tokens;
                 }
             }
         }
@@ -48,3 +54,112 @@ pub fn soxtype_impl(item: Item) -> TokenStream {
     };
     tokens.into_token_stream()
 }
+
+fn method_defs(ident: &TokenStream, methods: Vec<(String, Ident)>) -> TokenStream {
+    let mut inner_tokens = TokenStream::new();
+    for (method_name, method) in methods {
+        inner_tokens.extend(quote! [
+            (#method_name, SoxMethod{
+                func: static_func(#ident::#method)
+            }),
+        ]);
+    }
+    let array: TokenTree = Group::new(Delimiter::Bracket, inner_tokens).into();
+    array.into_token_stream()
+}
+
+#[derive(Default)]
+struct MethodOptions {
+    /// The name the method is exposed under, when it differs from the function name.
+    name: Option<String>,
+    /// Whether the method is called on the type object instead of on an instance.
+    is_static: bool,
+}
+
+/// Reads the arguments of `#[soxmethod(name = "...", static)]`; a bare `#[soxmethod]`
+/// exposes an instance method under the function name.
+fn method_options(attr: &Attribute) -> syn::Result<MethodOptions> {
+    let mut options = MethodOptions::default();
+    if let Meta::Path(_) = attr.meta {
+        return Ok(options);
+    }
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            let value: LitStr = meta.value()?.parse()?;
+            options.name = Some(value.value());
+            Ok(())
+        } else if meta.path.is_ident("static") {
+            options.is_static = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported soxmethod argument"))
+        }
+    })?;
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use crate::proc::soxtype_impl;
+
+    #[test]
+    fn test_method_names() {
+        let item = parse_quote! {
+            impl SoxThing {
+                #[soxmethod(name = "bool")]
+                pub fn bool_(&self) -> SoxBool {
+                    SoxBool::new(true)
+                }
+
+                #[soxmethod]
+                pub fn equals(&self, rhs: SoxObject) -> SoxBool {
+                    SoxBool::new(false)
+                }
+            }
+        };
+        let expanded = soxtype_impl(item).to_string();
+        assert!(
+            expanded.contains(r#"("bool" , SoxMethod { func : static_func (SoxThing :: bool_) })"#)
+        );
+        assert!(expanded
+            .contains(r#"("equals" , SoxMethod { func : static_func (SoxThing :: equals) })"#));
+    }
+
+    #[test]
+    fn test_static_methods() {
+        let item = parse_quote! {
+            impl SoxThing {
+                #[soxmethod(static)]
+                pub fn from_str(value: SoxString) -> SoxResult {
+                    Ok(value.into_ref())
+                }
+
+                #[soxmethod(static, name = "of")]
+                pub fn of_(value: SoxObject) -> SoxResult {
+                    Ok(value)
+                }
+            }
+        };
+        let expanded = soxtype_impl(item).to_string();
+        assert!(expanded.contains("const METHOD_DEFS : & 'static [(& 'static str , SoxMethod)] = & [] ;"));
+        assert!(expanded.contains(
+            r#"const STATIC_METHOD_DEFS : & 'static [(& 'static str , SoxMethod)] = & [("from_str" , SoxMethod { func : static_func (SoxThing :: from_str) }) , ("of" , SoxMethod { func : static_func (SoxThing :: of_) }) ,] ;"#
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_argument() {
+        let item = parse_quote! {
+            impl SoxThing {
+                #[soxmethod(alias = "bool")]
+                pub fn bool_(&self) -> SoxBool {
+                    SoxBool::new(true)
+                }
+            }
+        };
+        let expanded = soxtype_impl(item).to_string();
+        assert!(expanded.contains("compile_error"));
+    }
+}
@@ -0,0 +1,319 @@
+//! JSON dumps of parse trees, for tools that want to inspect what the parser produced.
+//!
+//! Every node is an object whose `"type"` is the name of its variant, with a field per
+//! child named as in the AST. Tokens become `{"lexeme", "line"}` objects. Resolver
+//! bookkeeping such as `VarRef`s is left out, so the output only depends on the source.
+
+use serde_json::{json, Value};
+
+use crate::expr::Expr;
+use crate::stmt::Stmt;
+use crate::token::{Literal, Token};
+
+pub fn expr_to_json(expr: &Expr) -> Value {
+    match expr {
+        Expr::Assign { name, value, .. } => json!({
+            "type": "Assign",
+            "name": token_to_json(name),
+            "value": expr_to_json(value),
+        }),
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        } => json!({
+            "type": "Binary",
+            "left": expr_to_json(left),
+            "operator": token_to_json(operator),
+            "right": expr_to_json(right),
+        }),
+        Expr::Block { statements, value } => json!({
+            "type": "Block",
+            "statements": stmts_to_json(statements),
+            "value": value.as_deref().map(expr_to_json),
+        }),
+        Expr::Call {
+            callee,
+            paren,
+            arguments,
+        } => json!({
+            "type": "Call",
+            "callee": expr_to_json(callee),
+            "paren": token_to_json(paren),
+            "arguments": exprs_to_json(arguments),
+        }),
+        Expr::Get { object, name } => json!({
+            "type": "Get",
+            "object": expr_to_json(object),
+            "name": token_to_json(name),
+        }),
+        Expr::Grouping { expr } => json!({
+            "type": "Grouping",
+            "expr": expr_to_json(expr),
+        }),
+        Expr::Index {
+            object,
+            bracket,
+            index,
+        } => json!({
+            "type": "Index",
+            "object": expr_to_json(object),
+            "bracket": token_to_json(bracket),
+            "index": expr_to_json(index),
+        }),
+        Expr::List { elements } => json!({
+            "type": "List",
+            "elements": exprs_to_json(elements),
+        }),
+        Expr::Literal { value } => json!({
+            "type": "Literal",
+            "value": literal_to_json(value),
+        }),
+        Expr::Variable { name, .. } => json!({
+            "type": "Variable",
+            "name": token_to_json(name),
+        }),
+        Expr::Logical {
+            left,
+            operator,
+            right,
+        } => json!({
+            "type": "Logical",
+            "left": expr_to_json(left),
+            "operator": token_to_json(operator),
+            "right": expr_to_json(right),
+        }),
+        Expr::Slice {
+            object,
+            bracket,
+            start,
+            end,
+        } => json!({
+            "type": "Slice",
+            "object": expr_to_json(object),
+            "bracket": token_to_json(bracket),
+            "start": start.as_deref().map(expr_to_json),
+            "end": end.as_deref().map(expr_to_json),
+        }),
+        Expr::Set {
+            object,
+            name,
+            value,
+        } => json!({
+            "type": "Set",
+            "object": expr_to_json(object),
+            "name": token_to_json(name),
+            "value": expr_to_json(value),
+        }),
+        Expr::SetIndex {
+            object,
+            bracket,
+            index,
+            value,
+        } => json!({
+            "type": "SetIndex",
+            "object": expr_to_json(object),
+            "bracket": token_to_json(bracket),
+            "index": expr_to_json(index),
+            "value": expr_to_json(value),
+        }),
+        Expr::Super {
+            keyword, method, ..
+        } => json!({
+            "type": "Super",
+            "keyword": token_to_json(keyword),
+            "method": token_to_json(method),
+        }),
+        Expr::This { keyword, .. } => json!({
+            "type": "This",
+            "keyword": token_to_json(keyword),
+        }),
+        Expr::Unary { operator, right } => json!({
+            "type": "Unary",
+            "operator": token_to_json(operator),
+            "right": expr_to_json(right),
+        }),
+    }
+}
+
+pub fn stmt_to_json(stmt: &Stmt) -> Value {
+    match stmt {
+        Stmt::Expression(expr) => json!({
+            "type": "Expression",
+            "expr": expr_to_json(expr),
+        }),
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => json!({
+            "type": "If",
+            "condition": expr_to_json(condition),
+            "then_branch": stmt_to_json(then_branch),
+            "else_branch": else_branch.as_ref().as_ref().map(stmt_to_json),
+        }),
+        Stmt::Print(values) => json!({
+            "type": "Print",
+            "values": exprs_to_json(values),
+        }),
+        Stmt::Return { keyword, value } => json!({
+            "type": "Return",
+            "keyword": token_to_json(keyword),
+            "value": value.as_ref().map(expr_to_json),
+        }),
+        Stmt::Var { name, initializer } => json!({
+            "type": "Var",
+            "name": token_to_json(name),
+            "initializer": initializer.as_ref().map(expr_to_json),
+        }),
+        Stmt::Const { name, initializer } => json!({
+            "type": "Const",
+            "name": token_to_json(name),
+            "initializer": expr_to_json(initializer),
+        }),
+        Stmt::While { condition, body } => json!({
+            "type": "While",
+            "condition": expr_to_json(condition),
+            "body": stmt_to_json(body),
+        }),
+        Stmt::For {
+            initializer,
+            condition,
+            increment,
+            body,
+        } => json!({
+            "type": "For",
+            "initializer": initializer.as_deref().map(stmt_to_json),
+            "condition": condition.as_ref().map(expr_to_json),
+            "increment": increment.as_ref().map(expr_to_json),
+            "body": stmt_to_json(body),
+        }),
+        Stmt::Continue { keyword } => json!({
+            "type": "Continue",
+            "keyword": token_to_json(keyword),
+        }),
+        Stmt::Block(statements) => json!({
+            "type": "Block",
+            "statements": stmts_to_json(statements),
+        }),
+        Stmt::Function {
+            name,
+            params,
+            defaults,
+            body,
+        } => json!({
+            "type": "Function",
+            "name": token_to_json(name),
+            "params": params.iter().map(token_to_json).collect::<Vec<Value>>(),
+            "defaults": defaults
+                .iter()
+                .map(|default| default.as_ref().map(expr_to_json))
+                .collect::<Vec<Option<Value>>>(),
+            "body": stmts_to_json(body),
+        }),
+        Stmt::Class {
+            name,
+            superclass,
+            methods,
+            overrides,
+            getters,
+        } => json!({
+            "type": "Class",
+            "name": token_to_json(name),
+            "superclass": superclass.as_ref().map(expr_to_json),
+            "methods": stmts_to_json(methods),
+            "overrides": overrides.iter().map(token_to_json).collect::<Vec<Value>>(),
+            "getters": getters.iter().map(token_to_json).collect::<Vec<Value>>(),
+        }),
+        Stmt::Throw { keyword, value } => json!({
+            "type": "Throw",
+            "keyword": token_to_json(keyword),
+            "value": expr_to_json(value),
+        }),
+        Stmt::Try {
+            body,
+            catch_name,
+            catch_body,
+        } => json!({
+            "type": "Try",
+            "body": stmts_to_json(body),
+            "catch_name": token_to_json(catch_name),
+            "catch_body": stmts_to_json(catch_body),
+        }),
+        Stmt::Match {
+            subject,
+            cases,
+            default,
+        } => json!({
+            "type": "Match",
+            "subject": expr_to_json(subject),
+            "cases": cases
+                .iter()
+                .map(|(value, body)| json!({
+                    "value": expr_to_json(value),
+                    "body": stmts_to_json(body),
+                }))
+                .collect::<Vec<Value>>(),
+            "default": default.as_deref().map(stmts_to_json),
+        }),
+    }
+}
+
+fn exprs_to_json(exprs: &[Expr]) -> Vec<Value> {
+    exprs.iter().map(expr_to_json).collect()
+}
+
+fn stmts_to_json(stmts: &[Stmt]) -> Vec<Value> {
+    stmts.iter().map(stmt_to_json).collect()
+}
+
+fn token_to_json(token: &Token) -> Value {
+    json!({
+        "lexeme": token.lexeme,
+        "line": token.line,
+    })
+}
+
+// non-finite floats have no JSON number, so they come out as null
+fn literal_to_json(literal: &Literal) -> Value {
+    match literal {
+        Literal::String(s) => json!(s),
+        Literal::Integer(i) => json!(i),
+        Literal::Float(f) => json!(f.0),
+        Literal::Boolean(b) => json!(b),
+        Literal::None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::ast_json::stmt_to_json;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_binary_nesting_follows_precedence() {
+        let ast = Parser::new(Lexer::lex("1 + 2 * 3;")).parse().unwrap();
+        let literal = |value| json!({ "type": "Literal", "value": value });
+        let token = |lexeme| json!({ "lexeme": lexeme, "line": 1 });
+        assert_eq!(
+            stmt_to_json(&ast[0]),
+            json!({
+                "type": "Expression",
+                "expr": {
+                    "type": "Binary",
+                    "left": literal(1),
+                    "operator": token("+"),
+                    "right": {
+                        "type": "Binary",
+                        "left": literal(2),
+                        "operator": token("*"),
+                        "right": literal(3),
+                    },
+                },
+            })
+        );
+    }
+}
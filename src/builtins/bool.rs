@@ -105,6 +105,7 @@ impl StaticType for SoxBool {
     fn create_slots() -> SoxTypeSlot {
         SoxTypeSlot {
             call: None,
+            new: None,
             methods: Self::METHOD_DEFS,
         }
     }
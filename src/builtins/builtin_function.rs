@@ -0,0 +1,111 @@
+use std::any::Any;
+
+use once_cell::sync::OnceCell;
+
+use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
+use crate::builtins::method::{FuncArgs, SoxMethod};
+use crate::builtins::r#type::{SoxType, SoxTypeSlot};
+use crate::core::{
+    Representable, SoxClassImpl, SoxObject, SoxObjectPayload, SoxRef, SoxResult, StaticType,
+};
+use crate::interpreter::Interpreter;
+
+/// A function implemented in Rust. When `receiver` is set, the function is a method of a
+/// builtin type bound to that object and the receiver is passed as the first argument.
+#[derive(Clone, Debug)]
+pub struct SoxBuiltinFunction {
+    pub name: String,
+    pub method: SoxMethod,
+    pub receiver: Option<SoxObject>,
+}
+
+impl SoxBuiltinFunction {
+    pub fn new(name: String, method: SoxMethod) -> Self {
+        Self {
+            name,
+            method,
+            receiver: None,
+        }
+    }
+
+    pub fn bound(name: String, method: SoxMethod, receiver: SoxObject) -> Self {
+        Self {
+            name,
+            method,
+            receiver: Some(receiver),
+        }
+    }
+
+    pub fn call(fo: SoxObject, args: FuncArgs, interpreter: &mut Interpreter) -> SoxResult {
+        if let Some(fo) = fo.as_builtin_func() {
+            let mut call_args = args.args;
+            if let Some(receiver) = fo.receiver.clone() {
+                call_args.insert(0, receiver);
+            }
+            (fo.method.func)(interpreter, FuncArgs::new(call_args))
+        } else {
+            let error = Exception::Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                "first argument to this call method should be a builtin function object",
+            ));
+            Err(error.into_ref())
+        }
+    }
+}
+
+impl SoxObjectPayload for SoxBuiltinFunction {
+    fn to_sox_type_value(obj: SoxObject) -> SoxRef<Self> {
+        obj.as_builtin_func().unwrap()
+    }
+
+    fn to_sox_object(&self, ref_type: SoxRef<Self>) -> SoxObject {
+        SoxObject::BuiltinFunction(ref_type)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_ref(self) -> SoxObject {
+        SoxRef::new(self).to_sox_object()
+    }
+
+    fn class(&self, i: &Interpreter) -> &'static SoxType {
+        i.types.builtin_func_type
+    }
+}
+
+impl SoxClassImpl for SoxBuiltinFunction {
+    const METHOD_DEFS: &'static [(&'static str, SoxMethod)] = &[];
+}
+
+impl StaticType for SoxBuiltinFunction {
+    const NAME: &'static str = "builtin_function";
+
+    fn static_cell() -> &'static OnceCell<SoxType> {
+        static CELL: OnceCell<SoxType> = OnceCell::new();
+        &CELL
+    }
+
+    fn create_slots() -> SoxTypeSlot {
+        SoxTypeSlot {
+            call: Some(Self::call),
+            new: None,
+            methods: Self::METHOD_DEFS,
+        }
+    }
+}
+
+/// Natives print as `<built-in function name>` and methods bound to a builtin object as
+/// `<built-in method name of type object>`.
+impl Representable for SoxBuiltinFunction {
+    fn repr(&self, i: &Interpreter) -> String {
+        match &self.receiver {
+            Some(receiver) => {
+                let type_name = receiver.sox_type(i).name.clone().unwrap_or_default();
+                format!("<built-in method {} of {} object>", self.name, type_name)
+            }
+            None => format!("<built-in function {}>", self.name),
+        }
+    }
+}
@@ -10,26 +10,117 @@ use std::fmt::Debug;
 #[derive(Clone, Debug)]
 pub enum Exception {
     Err(RuntimeError),
+    /// A value raised by a `throw` statement, kept intact so handlers can inspect it.
+    Thrown(SoxObject),
     Return(SoxObject),
+    /// Raised by a `continue` statement and caught by the innermost enclosing loop.
+    Continue,
 }
 
 impl Representable for Exception {
     fn repr(&self, i: &Interpreter) -> String {
         match &self {
             Exception::Err(v) => v.repr(i),
+            Exception::Thrown(v) => v.repr(i),
             Exception::Return(_) => "Return".to_string(),
+            Exception::Continue => "Continue".to_string(),
         }
     }
 }
+impl Exception {
+    /// Renders an uncaught exception; interpreter errors include where they were raised.
+    pub fn report(&self, i: &Interpreter) -> String {
+        match &self {
+            Exception::Err(v) => v.report(i),
+            _ => self.repr(i),
+        }
+    }
+}
+
 impl From<RuntimeError> for Exception {
     fn from(value: RuntimeError) -> Self {
         Exception::Err(value)
     }
 }
 
+/// Categories of runtime errors, mirroring the exception names scripts see.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorKind {
+    #[default]
+    RuntimeError,
+    TypeError,
+    NameError,
+    ValueError,
+    ZeroDivisionError,
+    AttributeError,
+    IndexError,
+    RecursionError,
+    AssertionError,
+}
+
+impl ErrorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::RuntimeError => "RuntimeError",
+            ErrorKind::TypeError => "TypeError",
+            ErrorKind::NameError => "NameError",
+            ErrorKind::ValueError => "ValueError",
+            ErrorKind::ZeroDivisionError => "ZeroDivisionError",
+            ErrorKind::AttributeError => "AttributeError",
+            ErrorKind::IndexError => "IndexError",
+            ErrorKind::RecursionError => "RecursionError",
+            ErrorKind::AssertionError => "AssertionError",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct RuntimeError {
     pub msg: String,
+    pub kind: ErrorKind,
+    /// The line being executed in the outermost frame the error has propagated to so far.
+    pub line: Option<usize>,
+    /// The functions the error propagated out of, innermost first, each with the line it
+    /// was executing.
+    pub traceback: Vec<(String, usize)>,
+}
+
+impl RuntimeError {
+    pub fn new<T: Into<String>>(kind: ErrorKind, msg: T) -> Self {
+        RuntimeError {
+            msg: msg.into(),
+            kind,
+            line: None,
+            traceback: vec![],
+        }
+    }
+
+    /// Records the line the error was raised on, unless it is already known.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Records that the error propagated out of `function`, which was called on `call_line`.
+    pub fn unwind(mut self, function: &str, call_line: usize) -> Self {
+        let line = self.line.unwrap_or(call_line);
+        self.traceback.push((function.to_string(), line));
+        self.line = Some(call_line);
+        self
+    }
+
+    /// Renders the error followed by the call stack it unwound, e.g.
+    /// `NameError: ...\n[line 2] in f()\n[line 5] in script`.
+    pub fn report(&self, i: &Interpreter) -> String {
+        let mut lines = vec![self.repr(i)];
+        for (function, line) in &self.traceback {
+            lines.push(format!("[line {}] in {}()", line, function));
+        }
+        if let Some(line) = self.line {
+            lines.push(format!("[line {}] in script", line));
+        }
+        lines.join("\n")
+    }
 }
 
 impl From<Exception> for RuntimeError {
@@ -37,20 +128,24 @@ impl From<Exception> for RuntimeError {
         if let Exception::Err(v) = value {
             v
         } else {
-            RuntimeError { msg: "".into() }
+            RuntimeError::default()
         }
     }
 }
 
 impl Representable for RuntimeError {
+    // errors without a specific kind (e.g. thrown values) are rendered as the bare message
     fn repr(&self, _i: &Interpreter) -> String {
-        self.msg.to_string()
+        match self.kind {
+            ErrorKind::RuntimeError => self.msg.to_string(),
+            kind => format!("{}: {}", kind.name(), self.msg),
+        }
     }
 }
 
 impl SoxObjectPayload for Exception {
-    fn to_sox_type_value(_obj: SoxObject) -> SoxRef<Self> {
-        todo!()
+    fn to_sox_type_value(obj: SoxObject) -> SoxRef<Self> {
+        obj.as_exception().unwrap()
     }
 
     fn to_sox_object(&self, ref_type: SoxRef<Self>) -> SoxObject {
@@ -58,7 +153,7 @@ impl SoxObjectPayload for Exception {
     }
 
     fn as_any(&self) -> &dyn Any {
-        todo!()
+        self
     }
 
     fn class(&self, i: &Interpreter) -> &'static SoxType {
@@ -75,7 +170,7 @@ impl StaticType for Exception {
     }
 
     fn create_slots() -> SoxTypeSlot {
-        SoxTypeSlot { call: None,             methods: Self::METHOD_DEFS,
+        SoxTypeSlot { call: None, new: None,            methods: Self::METHOD_DEFS,
         }
     }
 }
@@ -83,3 +178,21 @@ impl StaticType for Exception {
 impl SoxClassImpl for Exception {
     const METHOD_DEFS: &'static [(&'static str, SoxMethod)] = &[];
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
+    use crate::core::SoxObjectPayload;
+
+    #[test]
+    fn test_exception_payload_conversions() {
+        let error = Exception::Err(RuntimeError::new(ErrorKind::ValueError, "bad value"));
+        let obj = error.into_ref();
+        let exception = obj.as_exception().unwrap();
+        let downcast = exception.as_any().downcast_ref::<Exception>();
+        assert!(matches!(downcast, Some(Exception::Err(e)) if e.msg == "bad value"));
+
+        let exception = Exception::to_sox_type_value(obj);
+        assert!(matches!(&*exception.val, Exception::Err(_)));
+    }
+}
@@ -3,7 +3,8 @@ use std::any::Any;
 use std::ops::Deref;
 use macros::{soxmethod, soxtype};
 use crate::builtins::bool::SoxBool;
-use crate::builtins::method::{static_func, SoxMethod};
+use crate::builtins::exceptions::ErrorKind;
+use crate::builtins::method::{static_func, FuncArgs, SoxMethod};
 use crate::builtins::r#type::{SoxType, SoxTypeSlot};
 use crate::builtins::string::SoxString;
 use crate::core::{Representable, SoxClassImpl, SoxObject, SoxObjectPayload, SoxRef, SoxResult, StaticType, ToSoxResult, TryFromSoxObject};
@@ -21,6 +22,53 @@ impl SoxFloat {
         SoxFloat { value: val }
     }
 
+    /// Creates a float when the `float` type is called: zero with no arguments, otherwise
+    /// the float value of the number or decimal string passed in.
+    pub fn construct(_typ: SoxObject, args: FuncArgs, i: &mut Interpreter) -> SoxResult {
+        match args.args.as_slice() {
+            [] => Ok(SoxFloat::new(0.0).into_ref()),
+            [SoxObject::Float(v)] => Ok(SoxObject::Float(v.clone())),
+            [SoxObject::Int(v)] => Ok(SoxFloat::new(v.value as f64).into_ref()),
+            [SoxObject::String(v)] => SoxFloat::from_text(&v.value),
+            [other] => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "cannot convert '{}' to float",
+                    other.sox_type(i).name.clone().unwrap_or_default()
+                ),
+            )),
+            _ => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!("Expected at most 1 arguments but got {}.", args.args.len()),
+            )),
+        }
+    }
+
+    fn from_text(text: &str) -> SoxResult {
+        text.trim()
+            .parse::<f64>()
+            .map(|v| SoxFloat::new(v).into_ref())
+            .map_err(|_| {
+                Interpreter::typed_error(
+                    ErrorKind::ValueError,
+                    format!("invalid literal for float(): '{}'", text),
+                )
+            })
+    }
+
+    /// Parses a float from its decimal representation, e.g. `float.parse("3.14")`.
+    #[soxmethod(static)]
+    pub fn parse(value: SoxString) -> SoxResult {
+        SoxFloat::from_text(&value.value)
+    }
+
+    /// Zero (of either sign) is false, every other float is true. NaN is not equal to
+    /// zero, so it is true.
+    #[soxmethod]
+    pub fn bool(&self) -> SoxBool {
+        SoxBool::new(self.value != 0.0)
+    }
+
     #[soxmethod]
     pub fn equals(&self, other: SoxObject) -> SoxBool {
         if let Some(other_float) = other.as_float() {
@@ -65,6 +113,7 @@ impl StaticType for SoxFloat {
     fn create_slots() -> SoxTypeSlot {
         SoxTypeSlot {
             call: None,
+            new: Some(Self::construct),
             methods: Self::METHOD_DEFS,
         }
     }
@@ -99,7 +148,14 @@ impl From<f64> for SoxFloat {
 }
 
 impl Representable for SoxFloat {
+    /// Floats of magnitude 1e16 and above or below 1e-4 are written in scientific notation,
+    /// e.g. `1e300`, as Python does; other values keep their decimal form.
     fn repr(&self, _i: &Interpreter) -> String {
-        self.value.to_string()
+        let magnitude = self.value.abs();
+        if self.value.is_finite() && magnitude != 0.0 && !(1e-4..1e16).contains(&magnitude) {
+            format!("{:e}", self.value)
+        } else {
+            self.value.to_string()
+        }
     }
 }
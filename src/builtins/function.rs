@@ -3,7 +3,7 @@ use std::any::Any;
 use std::iter::zip;
 use std::ops::Deref;
 use crate::builtins::bool::SoxBool;
-use crate::builtins::exceptions::{Exception, RuntimeError};
+use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
 use crate::builtins::method::{static_func, FuncArgs, SoxMethod};
 use crate::builtins::none::SoxNone;
 use crate::builtins::r#type::{SoxType, SoxTypeSlot};
@@ -17,6 +17,12 @@ use crate::environment::EnvRef;
 use crate::interpreter::Interpreter;
 use crate::stmt::Stmt;
 
+/// How much stack a call needs left before it moves to a new segment; comfortably more
+/// than the Rust frames one sox call nests in a debug build.
+const STACK_RED_ZONE: usize = 256 * 1024;
+/// The size of each extra stack segment allocated for deep recursion.
+const STACK_SEGMENT_SIZE: usize = 4 * 1024 * 1024;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct SoxFunction {
     pub name: String,
@@ -24,6 +30,10 @@ pub struct SoxFunction {
     pub environment_ref: EnvRef,
     pub is_initializer: bool,
     pub arity: i8,
+    /// The class of the instance a method has been bound to, or `None` for a plain function.
+    pub bound_class: Option<String>,
+    /// Whether the method was declared with `get`, to be called when read as a property.
+    pub is_getter: bool,
 }
 
 impl SoxFunction {
@@ -34,11 +44,14 @@ impl SoxFunction {
             environment_ref,
             is_initializer,
             arity,
+            bound_class: None,
+            is_getter: false,
         }
     }
 
     pub fn bind(&self, instance: SoxObject, interp: &mut Interpreter) -> SoxResult {
-        if let SoxObject::TypeInstance(_) = instance {
+        if let SoxObject::TypeInstance(inst) = &instance {
+            let class_name = inst.class_ref().name.clone();
             let env_ref = interp
                 .environment
                 .new_local_env_at(self.environment_ref.clone());
@@ -52,6 +65,8 @@ impl SoxFunction {
                 environment_ref: env_ref,
                 is_initializer: self.is_initializer,
                 arity: self.arity,
+                bound_class: class_name,
+                is_getter: self.is_getter,
             };
             Ok(new_func.into_ref())
         } else {
@@ -61,68 +76,134 @@ impl SoxFunction {
         }
     }
 
+    /// What reading the method as a property of `instance` gives: the method bound to the
+    /// instance, or for a getter the value calling it returns.
+    pub fn property_value(&self, instance: SoxObject, interp: &mut Interpreter) -> SoxResult {
+        let bound_method = self.bind(instance, interp)?;
+        if self.is_getter {
+            SoxFunction::call(bound_method, FuncArgs::new(vec![]), interp)
+        } else {
+            Ok(bound_method)
+        }
+    }
+
+    pub fn required_arity(&self) -> i8 {
+        if let Stmt::Function { defaults, .. } = self.declaration.as_ref() {
+            defaults.iter().take_while(|d| d.is_none()).count() as i8
+        } else {
+            self.arity
+        }
+    }
+
+    pub fn check_arity(&self, arg_count: usize) -> SoxResult<()> {
+        let (min, max) = (self.required_arity() as usize, self.arity as usize);
+        if arg_count >= min && arg_count <= max {
+            return Ok(());
+        }
+        let msg = if min == max {
+            format!("Expected {} arguments but got {}.", max, arg_count)
+        } else {
+            format!(
+                "Expected between {} and {} arguments but got {}.",
+                min, max, arg_count
+            )
+        };
+        Err(Exception::Err(RuntimeError::new(ErrorKind::TypeError, msg)).into_ref())
+    }
+
     pub fn call(fo: SoxObject, args: FuncArgs, interpreter: &mut Interpreter) -> SoxResult {
-        if let Some(fo) = fo.as_func() {
-            if args.args.len() != fo.arity as usize {
-                let error = Exception::Err(RuntimeError {
-                    msg: format!(
-                        "Expected {} arguments but got {}.",
-                        fo.arity,
-                        args.args.len()
-                    ),
-                });
+        if let Some(func) = fo.as_func() {
+            drop(fo);
+            if interpreter.call_depth >= interpreter.max_call_depth {
+                let error = Exception::Err(RuntimeError::new(
+                    ErrorKind::RecursionError,
+                    "maximum recursion depth exceeded",
+                ));
                 return Err(error.into_ref());
             }
-            let previous_env_ref = interpreter.environment.active.clone();
-
-            interpreter.environment.active = fo.environment_ref.clone();
-            let mut return_value = Ok(SoxNone {}.into_ref());
-            if let Stmt::Function {
-                name: _,
-                params,
-                body,
-            } = *fo.declaration.clone()
-            {
-                let exec_ns = interpreter
-                    .environment
-                    .new_local_env_at(fo.environment_ref.clone());
-                let env = interpreter.environment.envs.get_mut(*exec_ns).unwrap();
-                for (param, arg) in zip(params, args.args.clone()) {
-                    env.define(param.lexeme, arg).expect("TODO: panic message");
+            let env_ref = func.environment_ref.clone();
+            interpreter.call_depth += 1;
+            // deep recursion outgrows the host's stack well before `max_call_depth` in
+            // debug builds, so calls run on extra stack segments once it runs low
+            let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT_SIZE, || {
+                func.invoke(args, interpreter)
+            });
+            interpreter.call_depth -= 1;
+            // a method bound for this call alone owns its namespace; release it (and any
+            // enclosing namespace only it kept alive) now that the function is gone
+            drop(func);
+            interpreter.environment.release(env_ref);
+            result
+        } else {
+            let error = Exception::Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                "first argument to this call method should be a function object",
+            ));
+            Err(error.into_ref())
+        }
+    }
+
+    fn invoke(&self, args: FuncArgs, interpreter: &mut Interpreter) -> SoxResult {
+        self.check_arity(args.args.len())?;
+        let previous_env_ref = interpreter.environment.active.clone();
+
+        interpreter.environment.active = self.environment_ref.clone();
+        let mut return_value = Ok(SoxNone {}.into_ref());
+        if let Stmt::Function {
+            name: _,
+            params,
+            defaults,
+            body,
+        } = *self.declaration.clone()
+        {
+            let mut call_args = args.args.clone();
+            for default in defaults.iter().skip(call_args.len()) {
+                let default = default
+                    .as_ref()
+                    .expect("missing arguments should have been caught by the arity check");
+                match interpreter.evaluate(default) {
+                    Ok(v) => call_args.push(v),
+                    Err(e) => {
+                        interpreter.environment.active = previous_env_ref;
+                        return Err(e);
+                    }
+                }
+            }
+            let exec_ns = interpreter
+                .environment
+                .new_local_env_at(self.environment_ref.clone());
+            let env = interpreter.environment.envs.get_mut(*exec_ns).unwrap();
+            for (param, arg) in zip(params, call_args) {
+                if let Err(e) = env.define(param.lexeme, arg) {
+                    interpreter.environment.active = previous_env_ref;
+                    return Err(e);
                 }
-                let ret = interpreter.execute_block(body.iter().collect(), Option::from(exec_ns));
-
-                if ret.is_err() {
-                    let exc = ret.err().unwrap().as_exception();
-                    if let Some(obj) = exc {
-                        match obj.deref() {
-                            Exception::Return(v) => {
-                                return_value = Ok(v.clone());
-                            }
-                            Exception::Err(v) => {
-                                let rv = Exception::Err(v.clone());
-                                return_value = Err(rv.into_ref());
-                            }
+            }
+            let ret = interpreter.execute_block(body.iter().collect(), Option::from(exec_ns));
+
+            if let Err(err) = ret {
+                if let Some(obj) = err.as_exception() {
+                    match obj.deref() {
+                        Exception::Return(v) => {
+                            return_value = Ok(v.clone());
+                        }
+                        Exception::Err(_) | Exception::Thrown(_) | Exception::Continue => {
+                            return_value = Err(err);
                         }
                     }
                 }
             }
-            if fo.is_initializer {
-
-                let v = interpreter.environment.find_and_get( "this");
-                interpreter.environment.active = previous_env_ref;
-                return v;
+        }
+        if self.is_initializer && return_value.is_ok() {
 
-            }
+            let v = interpreter.environment.find_and_get( "this");
             interpreter.environment.active = previous_env_ref;
-           
-            return_value
-        } else {
-            let error = Exception::Err(RuntimeError {
-                msg: "first argument to this call method should be a function object".to_string(),
-            });
-            Err(error.into_ref())
+            return v;
+
         }
+        interpreter.environment.active = previous_env_ref;
+       
+        return_value
     }
 
     pub fn equals(&self, other: &SoxObject) -> SoxBool {
@@ -180,6 +261,7 @@ impl StaticType for SoxFunction {
     fn create_slots() -> SoxTypeSlot {
         SoxTypeSlot {
             call: Some(Self::call),
+            new: None,
             //eq: None
             methods: Self::METHOD_DEFS,
 
@@ -208,9 +290,13 @@ impl ToSoxResult for SoxFunction {
     }
 }
 
+/// Functions print as `<Function name>` and methods bound to an instance as
+/// `<method name of Class object>`.
 impl Representable for SoxFunction {
     fn repr(&self, _i: &Interpreter) -> String {
-        let func_name = self.name.to_string();
-        format!("<Function {func_name}>")
+        match &self.bound_class {
+            Some(class_name) => format!("<method {} of {} object>", self.name, class_name),
+            None => format!("<Function {}>", self.name),
+        }
     }
 }
@@ -5,7 +5,8 @@ use std::rc::Rc;
 use once_cell::sync::OnceCell;
 use macros::{soxmethod, soxtype};
 use crate::builtins::bool::SoxBool;
-use crate::builtins::method::{static_func, SoxMethod};
+use crate::builtins::exceptions::ErrorKind;
+use crate::builtins::method::{static_func, FuncArgs, SoxMethod};
 use crate::builtins::r#type::{SoxType, SoxTypeSlot};
 use crate::builtins::string::SoxString;
 use crate::core::{Representable, SoxClassImpl, SoxObject, SoxObjectPayload, SoxRef, SoxResult, StaticType, ToSoxResult, TryFromSoxObject};
@@ -24,6 +25,58 @@ impl SoxInt {
         SoxInt { value: val }
     }
 
+    /// Creates an int when the `int` type is called: zero with no arguments, otherwise the
+    /// int value of the number or decimal string passed in.
+    pub fn construct(_typ: SoxObject, args: FuncArgs, i: &mut Interpreter) -> SoxResult {
+        match args.args.as_slice() {
+            [] => Ok(SoxInt::new(0).into_ref()),
+            [SoxObject::Int(v)] => Ok(SoxObject::Int(v.clone())),
+            [SoxObject::Float(v)] => Ok(SoxInt::new(v.value.trunc() as i64).into_ref()),
+            [SoxObject::String(v)] => SoxInt::from_text(&v.value),
+            [other] => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "cannot convert '{}' to int",
+                    other.sox_type(i).name.clone().unwrap_or_default()
+                ),
+            )),
+            _ => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!("Expected at most 1 arguments but got {}.", args.args.len()),
+            )),
+        }
+    }
+
+    fn from_text(text: &str) -> SoxResult {
+        text.trim()
+            .parse::<i64>()
+            .map(|v| SoxInt::new(v).into_ref())
+            .map_err(|_| {
+                Interpreter::typed_error(
+                    ErrorKind::ValueError,
+                    format!("invalid literal for int(): '{}'", text),
+                )
+            })
+    }
+
+    /// Parses an int from its decimal representation, e.g. `int.parse("42")`.
+    #[soxmethod(static)]
+    pub fn parse(value: SoxString) -> SoxResult {
+        SoxInt::from_text(&value.value)
+    }
+
+    /// An alias of `parse`.
+    #[soxmethod(static, name = "from_str")]
+    pub fn parse_alias(value: SoxString) -> SoxResult {
+        SoxInt::parse(value)
+    }
+
+    /// Zero is false, every other int is true.
+    #[soxmethod]
+    pub fn bool(&self) -> SoxBool {
+        SoxBool::new(self.value != 0)
+    }
+
     #[soxmethod]
     pub fn equals(&self, rhs: SoxObject) -> SoxBool {
         if let Some(rhs_int) = rhs.as_int() {
@@ -75,7 +128,7 @@ impl StaticType for SoxInt {
     }
 
     fn create_slots() -> SoxTypeSlot {
-        SoxTypeSlot { call: None,             methods: Self::METHOD_DEFS,
+        SoxTypeSlot { call: None, new: Some(Self::construct),            methods: Self::METHOD_DEFS,
         }
     }
 }
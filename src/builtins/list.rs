@@ -0,0 +1,322 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
+
+use macros::{soxmethod, soxtype};
+use once_cell::sync::OnceCell;
+
+use crate::builtins::bool::SoxBool;
+use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
+use crate::builtins::int::SoxInt;
+use crate::builtins::method::{static_func, FuncArgs, SoxMethod};
+use crate::builtins::none::SoxNone;
+use crate::builtins::r#type::{SoxType, SoxTypeSlot};
+use crate::core::{
+    Representable, SoxClassImpl, SoxObject, SoxObjectPayload, SoxRef, SoxResult, StaticType,
+    ToSoxResult,
+};
+use crate::interpreter::Interpreter;
+
+/// A mutable list of objects.
+///
+/// The elements live behind an `Rc` so that copies made with `copy`, `with_appended` and
+/// `without` can share storage with the list they came from. A list only clones its
+/// elements when it is mutated while that storage is still shared (copy-on-write), so
+/// aliases never observe mutations made through a copy and vice versa.
+#[derive(Debug)]
+pub struct SoxList {
+    elements: RefCell<Rc<Vec<SoxObject>>>,
+}
+
+#[soxtype]
+impl SoxList {
+    pub fn new(elements: Vec<SoxObject>) -> Self {
+        SoxList {
+            elements: RefCell::new(Rc::new(elements)),
+        }
+    }
+
+    fn shared(elements: Rc<Vec<SoxObject>>) -> Self {
+        SoxList {
+            elements: RefCell::new(elements),
+        }
+    }
+
+    /// Returns a snapshot of the current elements; later mutations of the list are not
+    /// reflected in it.
+    pub fn elements(&self) -> Rc<Vec<SoxObject>> {
+        self.elements.borrow().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.borrow().is_empty()
+    }
+
+    /// The element at `index`, counting from the end when it is negative.
+    pub fn get(&self, index: i64) -> Option<SoxObject> {
+        let elements = self.elements.borrow();
+        position(elements.len(), index).map(|idx| elements[idx].clone())
+    }
+
+    /// Replaces the element at `index`, counting from the end when it is negative. Returns
+    /// false, leaving the list unchanged, when the index is out of range.
+    pub fn set(&self, index: i64, value: SoxObject) -> bool {
+        let mut elements = self.elements.borrow_mut();
+        let Some(idx) = position(elements.len(), index) else {
+            return false;
+        };
+        Rc::make_mut(&mut elements)[idx] = value;
+        true
+    }
+
+    /// The elements from `start` up to but not including `end`; see `slice_range`.
+    pub fn slice(&self, start: Option<i64>, end: Option<i64>) -> SoxList {
+        let elements = self.elements.borrow();
+        SoxList::new(elements[slice_range(elements.len(), start, end)].to_vec())
+    }
+
+    /// Creates a list when the `list` type is called: empty with no arguments, or a copy
+    /// of the list passed in.
+    pub fn construct(_typ: SoxObject, args: FuncArgs, i: &mut Interpreter) -> SoxResult {
+        match args.args.as_slice() {
+            [] => Ok(SoxList::new(vec![]).into_ref()),
+            [SoxObject::List(other)] => Ok(SoxList::copy(other).into_ref()),
+            [other] => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "cannot convert '{}' to list",
+                    other.sox_type(i).name.clone().unwrap_or_default()
+                ),
+            )),
+            _ => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!("Expected at most 1 arguments but got {}.", args.args.len()),
+            )),
+        }
+    }
+
+    #[soxmethod(name = "len")]
+    pub fn len_method(zelf: &SoxRef<SoxList>) -> SoxInt {
+        SoxInt::new(zelf.len() as i64)
+    }
+
+    /// An alias of `len`.
+    #[soxmethod]
+    pub fn size(zelf: &SoxRef<SoxList>) -> SoxInt {
+        SoxList::len_method(zelf)
+    }
+
+    #[soxmethod(name = "is_empty")]
+    pub fn is_empty_method(zelf: &SoxRef<SoxList>) -> SoxBool {
+        SoxBool::new(zelf.is_empty())
+    }
+
+    #[soxmethod]
+    pub fn append(zelf: &SoxRef<SoxList>, item: SoxObject) -> SoxNone {
+        Rc::make_mut(&mut zelf.elements.borrow_mut()).push(item);
+        SoxNone
+    }
+
+    #[soxmethod]
+    pub fn copy(zelf: &SoxRef<SoxList>) -> SoxList {
+        SoxList::shared(zelf.elements())
+    }
+
+    #[soxmethod]
+    pub fn with_appended(zelf: &SoxRef<SoxList>, item: SoxObject) -> SoxList {
+        let mut elements = zelf.elements();
+        Rc::make_mut(&mut elements).push(item);
+        SoxList::shared(elements)
+    }
+
+    /// The position of the first element equal to the argument, or -1 when there is none.
+    #[soxmethod]
+    pub fn index_of(i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+        let (list, value) = SoxList::receiver_and_arg(&args)?;
+        for (idx, element) in list.elements().iter().enumerate() {
+            if i.values_equal(element, &value)? {
+                return Ok(SoxInt::new(idx as i64).into_ref());
+            }
+        }
+        Ok(SoxInt::new(-1).into_ref())
+    }
+
+    /// The first element the predicate passed in returns a truthy value for, or None.
+    #[soxmethod]
+    pub fn find(i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+        let (list, predicate) = SoxList::receiver_and_arg(&args)?;
+        for element in list.elements().iter() {
+            let matched = i.call(predicate.clone(), FuncArgs::new(vec![element.clone()]))?;
+            if matched.try_into_rust_bool(i) {
+                return Ok(element.clone());
+            }
+        }
+        Ok(SoxNone.into_ref())
+    }
+
+    // unpacks the arguments of a method bound to a list that takes one argument
+    fn receiver_and_arg(args: &FuncArgs) -> SoxResult<(SoxRef<SoxList>, SoxObject)> {
+        match args.args.as_slice() {
+            [SoxObject::List(list), arg] => Ok((list.clone(), arg.clone())),
+            _ => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "Expected 1 arguments but got {}.",
+                    args.args.len().saturating_sub(1)
+                ),
+            )),
+        }
+    }
+
+    #[soxmethod]
+    pub fn without(zelf: &SoxRef<SoxList>, index: SoxInt) -> SoxResult {
+        let mut elements = zelf.elements();
+        if index.value < 0 || index.value as usize >= elements.len() {
+            let error = Exception::Err(RuntimeError::new(
+                ErrorKind::IndexError,
+                "list index out of range",
+            ));
+            return Err(error.into_ref());
+        }
+        Rc::make_mut(&mut elements).remove(index.value as usize);
+        Ok(SoxList::shared(elements).into_ref())
+    }
+}
+
+/// Converts an index into a position in a sequence of `len` items, counting from the end
+/// when the index is negative. Returns `None` when it is out of range.
+pub(crate) fn position(len: usize, index: i64) -> Option<usize> {
+    let index = if index < 0 { index + len as i64 } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+/// The positions a slice of a sequence of `len` items covers. As in Python, negative bounds
+/// count from the end, missing bounds default to the ends of the sequence, bounds out of
+/// range are clamped and a start at or past the end gives an empty slice.
+pub(crate) fn slice_range(len: usize, start: Option<i64>, end: Option<i64>) -> Range<usize> {
+    let clamp = |bound: i64| {
+        let bound = if bound < 0 { bound + len as i64 } else { bound };
+        bound.clamp(0, len as i64) as usize
+    };
+    let start = start.map_or(0, clamp);
+    let end = end.map_or(len, clamp);
+    start..end.max(start)
+}
+
+impl SoxObjectPayload for SoxList {
+    fn to_sox_type_value(obj: SoxObject) -> SoxRef<Self> {
+        obj.as_list().unwrap()
+    }
+
+    fn to_sox_object(&self, ref_type: SoxRef<Self>) -> SoxObject {
+        SoxObject::List(ref_type)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_ref(self) -> SoxObject {
+        SoxRef::new(self).to_sox_object()
+    }
+
+    fn class(&self, i: &Interpreter) -> &'static SoxType {
+        i.types.list_type
+    }
+}
+
+impl StaticType for SoxList {
+    const NAME: &'static str = "list";
+
+    fn static_cell() -> &'static OnceCell<SoxType> {
+        static CELL: OnceCell<SoxType> = OnceCell::new();
+        &CELL
+    }
+
+    fn create_slots() -> SoxTypeSlot {
+        SoxTypeSlot {
+            call: None,
+            new: Some(Self::construct),
+            methods: Self::METHOD_DEFS,
+        }
+    }
+}
+
+impl ToSoxResult for SoxList {
+    fn to_sox_result(self, _i: &Interpreter) -> SoxResult {
+        Ok(self.into_ref())
+    }
+}
+
+impl Representable for SoxList {
+    fn repr(&self, i: &Interpreter) -> String {
+        let elements = self
+            .elements()
+            .iter()
+            .map(|element| element.nested_repr(i))
+            .collect::<Vec<String>>();
+        format!("[{}]", elements.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::builtins::int::SoxInt;
+    use crate::builtins::list::{slice_range, SoxList};
+    use crate::builtins::string::SoxString;
+    use crate::core::{Representable, SoxObjectPayload, SoxRef};
+    use crate::interpreter::Interpreter;
+
+    fn int_list(values: &[i64]) -> SoxRef<SoxList> {
+        let elements = values.iter().map(|v| SoxInt::new(*v).into_ref()).collect();
+        SoxRef::new(SoxList::new(elements))
+    }
+
+    #[test]
+    fn test_copy_shares_storage_until_mutated() {
+        let original = int_list(&[1, 2, 3]);
+        let copy = SoxRef::new(SoxList::copy(&original));
+        assert!(Rc::ptr_eq(&original.elements(), &copy.elements()));
+
+        SoxList::append(&copy, SoxInt::new(4).into_ref());
+        assert!(!Rc::ptr_eq(&original.elements(), &copy.elements()));
+        assert_eq!(original.len(), 3);
+        assert_eq!(copy.len(), 4);
+    }
+
+    #[test]
+    fn test_with_appended_leaves_original_unchanged() {
+        let original = int_list(&[1, 2]);
+        let appended = SoxList::with_appended(&original, SoxInt::new(3).into_ref());
+        assert_eq!(original.len(), 2);
+        assert_eq!(appended.len(), 3);
+    }
+
+    #[test]
+    fn test_repr_quotes_string_elements() {
+        let interpreter = Interpreter::new();
+        let list = SoxList::new(vec![
+            SoxString::new("a").into_ref(),
+            SoxString::new("say \"hi\"").into_ref(),
+            SoxInt::new(1).into_ref(),
+        ]);
+        assert_eq!(list.repr(&interpreter), r#"["a", "say \"hi\"", 1]"#);
+    }
+
+    #[test]
+    fn test_slice_range_clamps_bounds() {
+        assert_eq!(slice_range(5, Some(1), Some(3)), 1..3);
+        assert_eq!(slice_range(5, None, Some(2)), 0..2);
+        assert_eq!(slice_range(5, Some(-2), None), 3..5);
+        assert_eq!(slice_range(5, Some(-10), Some(10)), 0..5);
+        assert_eq!(slice_range(5, Some(4), Some(1)), 4..4);
+        assert_eq!(slice_range(5, Some(7), None), 5..5);
+    }
+}
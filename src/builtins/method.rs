@@ -1,10 +1,10 @@
-use crate::builtins::exceptions::{Exception, RuntimeError};
+use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 use crate::core::{SoxObject, SoxObjectPayload, SoxResult, ToSoxResult, TryFromSoxObject};
 use crate::interpreter::Interpreter;
 
-pub type SoxNativeFunction = dyn Fn(&Interpreter, FuncArgs) -> SoxResult;
+pub type SoxNativeFunction = dyn Fn(&mut Interpreter, FuncArgs) -> SoxResult;
 
 #[derive(Clone)]
 pub struct SoxMethod {
@@ -12,8 +12,10 @@ pub struct SoxMethod {
 }
 
 impl Debug for SoxMethod {
-    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SoxMethod")
+            .field("func", &(self.func as *const SoxNativeFunction as *const ()))
+            .finish()
     }
 }
 
@@ -26,7 +28,7 @@ impl SoxMethod {
 }
 
 pub trait NativeFn<K, R>: Sized + 'static {
-    fn call(&self, i: &Interpreter, arg: FuncArgs) -> SoxResult;
+    fn call(&self, i: &mut Interpreter, arg: FuncArgs) -> SoxResult;
 
     const STATIC_FUNC: &'static SoxNativeFunction = {
         if std::mem::size_of::<Self>() == 0 {
@@ -85,9 +87,10 @@ impl<T: TryFromSoxObject> FromArgs for T {
         let val = if let Some(v) = args.take_positional() {
             T::try_from_sox_object(i, v.clone())
         } else {
-            Err(Exception::Err(RuntimeError {
-                msg: "Too few argument supplied to function".into(),
-            })
+            Err(Exception::Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                "Too few argument supplied to function",
+            ))
             .into_ref())
         };
         val
@@ -126,24 +129,49 @@ pub struct BorrowedParam<T>(PhantomData<T>);
 
 pub struct OwnedParam<T>(PhantomData<T>);
 
+pub struct InterpreterParam;
+
+pub struct MutInterpreterParam;
+
 impl<F, R> NativeFn<(), R> for F
 where
     F: Fn() -> R + 'static,
     R: ToSoxResult,
 {
-    fn call(&self, i: &Interpreter, _args: FuncArgs) -> SoxResult {
+    fn call(&self, i: &mut Interpreter, _args: FuncArgs) -> SoxResult {
         (self)().to_sox_result(i)
     }
 }
 
+impl<F, R> NativeFn<(InterpreterParam,), R> for F
+where
+    F: Fn(&Interpreter, FuncArgs) -> R + 'static,
+    R: ToSoxResult,
+{
+    fn call(&self, i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+        (self)(i, args).to_sox_result(i)
+    }
+}
+
+/// Natives that call back into the interpreter, e.g. to invoke a function passed to them.
+impl<F, R> NativeFn<(MutInterpreterParam,), R> for F
+where
+    F: Fn(&mut Interpreter, FuncArgs) -> R + 'static,
+    R: ToSoxResult,
+{
+    fn call(&self, i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+        (self)(i, args).to_sox_result(i)
+    }
+}
+
 impl<F, T1, R> NativeFn<(OwnedParam<T1>,), R> for F
 where
     F: Fn(T1) -> R + 'static,
     T1: FromArgs,
     R: ToSoxResult,
 {
-    fn call(&self, i: &Interpreter, mut args: FuncArgs) -> SoxResult {
-        let (zelf,) = (args.bind::<(T1,)>(i)).expect("Failed to bind function arguments.");
+    fn call(&self, i: &mut Interpreter, mut args: FuncArgs) -> SoxResult {
+        let (zelf,) = args.bind::<(T1,)>(i)?;
         (self)(zelf).to_sox_result(i)
     }
 }
@@ -154,8 +182,8 @@ where
     S: FromArgs,
     R: ToSoxResult,
 {
-    fn call(&self, i: &Interpreter, mut args: FuncArgs) -> SoxResult {
-        let (zelf,) = (args.bind::<(S,)>(i)).expect("Failed to bind function arguments.");
+    fn call(&self, i: &mut Interpreter, mut args: FuncArgs) -> SoxResult {
+        let (zelf,) = args.bind::<(S,)>(i)?;
         (self)(&zelf).to_sox_result(i)
     }
 }
@@ -167,8 +195,8 @@ where
     S1: FromArgs,
     R: ToSoxResult,
 {
-    fn call(&self, i: &Interpreter, mut args: FuncArgs) -> SoxResult {
-        let (zelf, s1) = (args.bind::<(S, S1)>(i)).expect("Failed to bind function arguments.");
+    fn call(&self, i: &mut Interpreter, mut args: FuncArgs) -> SoxResult {
+        let (zelf, s1) = args.bind::<(S, S1)>(i)?;
         (self)(&zelf, s1).to_sox_result(i)
     }
 }
@@ -181,10 +209,8 @@ where
     T3: FromArgs,
     R: ToSoxResult,
 {
-    fn call(&self, i: &Interpreter, mut args: FuncArgs) -> SoxResult {
-        let (zelf, v1, v2) = args
-            .bind::<(T1, T2, T3)>(i)
-            .expect("Failed to bind function arguments.");
+    fn call(&self, i: &mut Interpreter, mut args: FuncArgs) -> SoxResult {
+        let (zelf, v1, v2) = args.bind::<(T1, T2, T3)>(i)?;
         (self)(zelf, v1, v2).to_sox_result(i)
     }
 }
@@ -194,3 +220,18 @@ impl FromArgs for FuncArgs {
         Ok(args.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+
+    #[test]
+    fn test_debug_format_of_types() {
+        let interpreter = Interpreter::new();
+        let formatted = format!("{:?}", interpreter.types.int_type);
+        assert!(formatted.contains("SoxMethod { func: 0x"));
+
+        let method = interpreter.types.int_type.methods.get("equals").unwrap();
+        assert!(format!("{:?}", method).starts_with("SoxMethod"));
+    }
+}
@@ -1,8 +1,11 @@
+pub mod builtin_function;
 pub mod exceptions;
 pub mod float;
 pub mod function;
 pub mod int;
+pub mod list;
 pub mod method;
+pub mod natives;
 pub mod none;
 pub mod string;
 
@@ -0,0 +1,441 @@
+use std::cmp::Ordering;
+use std::io;
+use std::io::{BufRead, Write};
+
+use crate::builtins::exceptions::ErrorKind;
+use crate::builtins::list::SoxList;
+use crate::builtins::method::{static_func, FuncArgs, SoxMethod};
+use crate::core::{SoxObject, SoxObjectPayload, SoxResult};
+use crate::interpreter::Interpreter;
+
+/// Functions installed in the global scope of every interpreter.
+pub const NATIVES: &[(&str, SoxMethod)] = &[
+    (
+        "type",
+        SoxMethod {
+            func: static_func(type_),
+        },
+    ),
+    (
+        "str",
+        SoxMethod {
+            func: static_func(str_),
+        },
+    ),
+    (
+        "input",
+        SoxMethod {
+            func: static_func(input),
+        },
+    ),
+    (
+        "len",
+        SoxMethod {
+            func: static_func(len),
+        },
+    ),
+    (
+        "write",
+        SoxMethod {
+            func: static_func(write),
+        },
+    ),
+    (
+        "sqrt",
+        SoxMethod {
+            func: static_func(sqrt),
+        },
+    ),
+    (
+        "floor",
+        SoxMethod {
+            func: static_func(floor),
+        },
+    ),
+    (
+        "ceil",
+        SoxMethod {
+            func: static_func(ceil),
+        },
+    ),
+    (
+        "abs",
+        SoxMethod {
+            func: static_func(abs),
+        },
+    ),
+    (
+        "sorted",
+        SoxMethod {
+            func: static_func(sorted),
+        },
+    ),
+    (
+        "casecmp",
+        SoxMethod {
+            func: static_func(casecmp),
+        },
+    ),
+    (
+        "arity",
+        SoxMethod {
+            func: static_func(arity),
+        },
+    ),
+    (
+        "is_callable",
+        SoxMethod {
+            func: static_func(is_callable),
+        },
+    ),
+    (
+        "assert_throws",
+        SoxMethod {
+            func: static_func(assert_throws),
+        },
+    ),
+];
+
+fn check_arg_count(args: &FuncArgs, expected: usize) -> SoxResult<()> {
+    if args.args.len() == expected {
+        Ok(())
+    } else {
+        Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!(
+                "Expected {} arguments but got {}.",
+                expected,
+                args.args.len()
+            ),
+        ))
+    }
+}
+
+fn type_name(i: &Interpreter, obj: &SoxObject) -> String {
+    obj.sox_type(i).name.clone().unwrap_or_default()
+}
+
+fn type_(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let obj = &args.args[0];
+    let typ = if let SoxObject::TypeInstance(inst) = obj {
+        Some(inst.class_ref())
+    } else {
+        i.types.type_object(obj.sox_type(i))
+    };
+    typ.map(|typ| typ.to_sox_object()).ok_or_else(|| {
+        Interpreter::runtime_error(format!("Could not find the type of {}", obj.repr(i)))
+    })
+}
+
+fn str_(i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let text = i.stringify(&args.args[0])?;
+    Ok(i.new_string(text))
+}
+
+fn number_arg(i: &Interpreter, name: &str, obj: &SoxObject) -> SoxResult<f64> {
+    match obj {
+        SoxObject::Int(v) => Ok(v.value as f64),
+        SoxObject::Float(v) => Ok(v.value),
+        _ => Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!("{}() expects a number, not '{}'", name, type_name(i, obj)),
+        )),
+    }
+}
+
+fn sqrt(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let value = number_arg(i, "sqrt", &args.args[0])?;
+    if value < 0.0 {
+        return Err(Interpreter::typed_error(
+            ErrorKind::ValueError,
+            "math domain error".into(),
+        ));
+    }
+    Ok(i.new_float(value.sqrt()))
+}
+
+fn floor(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let value = number_arg(i, "floor", &args.args[0])?;
+    Ok(i.new_int(value.floor() as i64))
+}
+
+fn ceil(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let value = number_arg(i, "ceil", &args.args[0])?;
+    Ok(i.new_int(value.ceil() as i64))
+}
+
+fn abs(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let obj = &args.args[0];
+    match obj {
+        SoxObject::Int(v) => Ok(i.new_int(v.value.abs())),
+        SoxObject::Float(v) => Ok(i.new_float(v.value.abs())),
+        _ => Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!("abs() expects a number, not '{}'", type_name(i, obj)),
+        )),
+    }
+}
+
+/// The number of elements in a list or characters in a string.
+fn len(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let obj = &args.args[0];
+    match obj {
+        SoxObject::List(list) => Ok(i.new_int(list.len() as i64)),
+        SoxObject::String(s) => Ok(i.new_int(s.value.chars().count() as i64)),
+        _ => Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!("object of type '{}' has no len()", type_name(i, obj)),
+        )),
+    }
+}
+
+/// Orders two numbers or two strings; other combinations can't be compared.
+fn compare(i: &Interpreter, lhs: &SoxObject, rhs: &SoxObject) -> SoxResult<Ordering> {
+    match (lhs, rhs) {
+        (SoxObject::Int(a), SoxObject::Int(b)) => Ok(a.value.cmp(&b.value)),
+        (SoxObject::String(a), SoxObject::String(b)) => Ok(a.value.cmp(&b.value)),
+        (SoxObject::Int(_) | SoxObject::Float(_), SoxObject::Int(_) | SoxObject::Float(_)) => {
+            let a = number_arg(i, "sorted", lhs)?;
+            let b = number_arg(i, "sorted", rhs)?;
+            Ok(a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+        }
+        _ => Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!(
+                "cannot compare '{}' with '{}'",
+                type_name(i, lhs),
+                type_name(i, rhs)
+            ),
+        )),
+    }
+}
+
+/// Returns a sorted copy of a list, ordering elements by the value `key` (if given)
+/// returns for them.
+fn sorted(i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+    let (list, key) = match args.args.as_slice() {
+        [list] => (list, None),
+        [list, key] => (list, Some(key)),
+        _ => {
+            return Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "Expected between 1 and 2 arguments but got {}.",
+                    args.args.len()
+                ),
+            ))
+        }
+    };
+    let Some(list) = list.as_list() else {
+        return Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!("sorted() expects a list, not '{}'", type_name(i, list)),
+        ));
+    };
+    let elements = list.elements();
+    let keys = match key {
+        Some(key) => elements
+            .iter()
+            .map(|element| i.call(key.clone(), FuncArgs::new(vec![element.clone()])))
+            .collect::<SoxResult<Vec<SoxObject>>>()?,
+        None => elements.to_vec(),
+    };
+    // keys that can't be compared are rejected before sorting, so the error names the
+    // first mismatched pair in list order rather than whichever pair the sort tries first
+    if let [first, rest @ ..] = keys.as_slice() {
+        for key in rest {
+            compare(i, first, key)?;
+        }
+    }
+    let mut order = (0..elements.len()).collect::<Vec<usize>>();
+    order.sort_by(|a, b| compare(i, &keys[*a], &keys[*b]).unwrap_or(Ordering::Equal));
+    let elements = order.into_iter().map(|idx| elements[idx].clone()).collect();
+    Ok(SoxList::new(elements).into_ref())
+}
+
+/// Compares two strings ignoring case, returning -1, 0 or 1.
+fn casecmp(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 2)?;
+    let (Some(lhs), Some(rhs)) = (args.args[0].as_string(), args.args[1].as_string()) else {
+        return Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            "casecmp() expects two strings".into(),
+        ));
+    };
+    let ordering = lhs.value.to_lowercase().cmp(&rhs.value.to_lowercase());
+    Ok(i.new_int(ordering as i64))
+}
+
+/// The number of parameters a function declares, or that a class's initializer takes.
+fn arity(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let obj = &args.args[0];
+    match obj {
+        SoxObject::Function(func) => Ok(i.new_int(func.arity as i64)),
+        SoxObject::Type(typ) => Ok(i.new_int(typ.arity() as i64)),
+        _ => Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!("arity() expects a function or class, not '{}'", type_name(i, obj)),
+        )),
+    }
+}
+
+/// Whether the value can be called, i.e. its type has a `call` slot.
+fn is_callable(i: &Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    Ok(i.new_bool(args.args[0].sox_type(i).slots.call.is_some()))
+}
+
+/// Calls a function with no arguments and raises an `AssertionError` unless it throws. With
+/// a second argument, the error (as a `catch` clause would see it) must also contain that
+/// text. Returns what a `catch` clause would have caught.
+fn assert_throws(i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+    let (func, expected) = match args.args.as_slice() {
+        [func] => (func.clone(), None),
+        [func, SoxObject::String(expected)] => (func.clone(), Some(expected.value.clone())),
+        [_, other] => {
+            return Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "assert_throws() expects a string message, not '{}'",
+                    type_name(i, other)
+                ),
+            ))
+        }
+        _ => {
+            return Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "Expected between 1 and 2 arguments but got {}.",
+                    args.args.len()
+                ),
+            ))
+        }
+    };
+    let error = match i.call(func, FuncArgs::new(vec![])) {
+        Ok(_) => {
+            return Err(Interpreter::typed_error(
+                ErrorKind::AssertionError,
+                "expected an error but none was thrown".into(),
+            ))
+        }
+        Err(error) => error,
+    };
+    let Some(caught) = i.caught_value(&error) else {
+        return Err(error);
+    };
+    if let Some(expected) = expected {
+        let message = i.stringify(&caught)?;
+        if !message.contains(&expected) {
+            return Err(Interpreter::typed_error(
+                ErrorKind::AssertionError,
+                format!("expected an error containing '{expected}' but got '{message}'"),
+            ));
+        }
+    }
+    Ok(caught)
+}
+
+fn input(i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+    if args.args.len() > 1 {
+        return Err(Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!("Expected at most 1 arguments but got {}.", args.args.len()),
+        ));
+    }
+    let prompt = args.args.first().map(|prompt| prompt.repr(i));
+    let line = read_input(prompt.as_deref(), &mut io::stdin().lock(), &mut i.output);
+    match line {
+        Ok(Some(line)) => Ok(i.new_string(line)),
+        Ok(None) => Ok(i.new_none()),
+        Err(e) => Err(Interpreter::runtime_error(format!(
+            "Failed to read input - {}",
+            e
+        ))),
+    }
+}
+
+/// Prints a value like `print` does, but without a trailing newline.
+fn write(i: &mut Interpreter, args: FuncArgs) -> SoxResult {
+    check_arg_count(&args, 1)?;
+    let text = i.stringify(&args.args[0])?;
+    write_output(&text, &mut i.output)
+        .map_err(|e| Interpreter::runtime_error(format!("Failed to write output - {}", e)))?;
+    Ok(i.new_none())
+}
+
+/// Writes `text` as is and flushes, so partial lines show up straight away.
+fn write_output<W: Write>(text: &str, writer: &mut W) -> io::Result<()> {
+    write!(writer, "{}", text)?;
+    writer.flush()
+}
+
+/// Writes the prompt and flushes the writer, so that earlier output comes before it, then
+/// reads a single line without its line terminator. Returns `None` once the reader is
+/// exhausted.
+fn read_input<R: BufRead, W: Write>(
+    prompt: Option<&str>,
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<Option<String>> {
+    if let Some(prompt) = prompt {
+        write!(writer, "{}", prompt)?;
+    }
+    writer.flush()?;
+    let mut buffer = String::new();
+    if reader.read_line(&mut buffer)? == 0 {
+        return Ok(None);
+    }
+    let line = buffer.strip_suffix('\n').unwrap_or(&buffer);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    Ok(Some(line.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufWriter, Cursor, Write};
+
+    use crate::builtins::natives::{read_input, write_output};
+
+    #[test]
+    fn test_read_input() {
+        let mut reader = Cursor::new("first line\nsecond line\n");
+        let mut output = vec![];
+        let line = read_input(Some("name? "), &mut reader, &mut output).unwrap();
+        assert_eq!(line, Some("first line".to_string()));
+        assert_eq!(output, b"name? ");
+
+        let line = read_input(None, &mut reader, &mut output).unwrap();
+        assert_eq!(line, Some("second line".to_string()));
+
+        let line = read_input(None, &mut reader, &mut output).unwrap();
+        assert_eq!(line, None);
+    }
+
+    #[test]
+    fn test_read_input_flushes_earlier_output() {
+        let mut reader = Cursor::new("answer\n");
+        let mut output = BufWriter::new(vec![]);
+        write!(output, "question\n").unwrap();
+        read_input(None, &mut reader, &mut output).unwrap();
+        assert_eq!(output.get_ref(), b"question\n");
+
+        read_input(Some("> "), &mut Cursor::new(""), &mut output).unwrap();
+        assert_eq!(output.get_ref(), b"question\n> ");
+    }
+
+    #[test]
+    fn test_write_output() {
+        let mut output = vec![];
+        write_output("name? ", &mut output).unwrap();
+        write_output("42", &mut output).unwrap();
+        assert_eq!(output, b"name? 42");
+    }
+}
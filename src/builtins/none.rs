@@ -77,6 +77,7 @@ impl StaticType for SoxNone {
     fn create_slots() -> SoxTypeSlot {
         SoxTypeSlot {
             call: None,
+            new: None,
             methods: Self::METHOD_DEFS,
         }
     }
@@ -3,7 +3,10 @@ use std::ops::Deref;
 pub use once_cell::sync::{Lazy, OnceCell};
 use macros::{soxmethod, soxtype};
 use crate::builtins::bool::SoxBool;
-use crate::builtins::method::{static_func, SoxMethod};
+use crate::builtins::exceptions::ErrorKind;
+use crate::builtins::int::SoxInt;
+use crate::builtins::list::{position, slice_range, SoxList};
+use crate::builtins::method::{static_func, FuncArgs, SoxMethod};
 use crate::builtins::r#type::{SoxType, SoxTypeSlot};
 use crate::core::{Representable, SoxClassImpl, SoxResult, ToSoxResult, TryFromSoxObject};
 use crate::core::{SoxObject, SoxObjectPayload, SoxRef, StaticType};
@@ -21,6 +24,122 @@ impl SoxString {
         SoxString { value: val.into() }
     }
 
+    /// Creates a string when the `string` type is called: empty with no arguments,
+    /// otherwise the value passed in as `print` would show it.
+    pub fn construct(_typ: SoxObject, args: FuncArgs, i: &mut Interpreter) -> SoxResult {
+        match args.args.as_slice() {
+            [] => Ok(SoxString::new("").into_ref()),
+            [value] => Ok(SoxString::new(i.stringify(value)?).into_ref()),
+            _ => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!("Expected at most 1 arguments but got {}.", args.args.len()),
+            )),
+        }
+    }
+
+    /// The character at `index`, counting from the end when it is negative.
+    pub fn get(&self, index: i64) -> Option<SoxString> {
+        let chars = self.value.chars().collect::<Vec<char>>();
+        position(chars.len(), index).map(|idx| SoxString::new(chars[idx]))
+    }
+
+    /// The characters from `start` up to but not including `end`; see `slice_range`.
+    pub fn slice(&self, start: Option<i64>, end: Option<i64>) -> SoxString {
+        let chars = self.value.chars().collect::<Vec<char>>();
+        SoxString::new(chars[slice_range(chars.len(), start, end)].iter().collect::<String>())
+    }
+
+    /// The string as it would be written in source, used when strings are shown inside
+    /// containers so that `["a, b"]` and `["a", "b"]` can be told apart.
+    pub fn quoted(&self) -> String {
+        let mut quoted = String::with_capacity(self.value.len() + 2);
+        quoted.push('"');
+        for ch in self.value.chars() {
+            match ch {
+                '"' => quoted.push_str("\\\""),
+                '\\' => quoted.push_str("\\\\"),
+                '\n' => quoted.push_str("\\n"),
+                _ => quoted.push(ch),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    #[soxmethod]
+    pub fn lower(&self) -> SoxString {
+        SoxString::new(self.value.to_lowercase())
+    }
+
+    /// The character at `index`, counting from the end when it is negative.
+    #[soxmethod]
+    pub fn char_at(&self, index: SoxInt) -> SoxResult {
+        match self.get(index.value) {
+            Some(character) => Ok(character.into_ref()),
+            None => Err(Interpreter::typed_error(
+                ErrorKind::IndexError,
+                "string index out of range".into(),
+            )),
+        }
+    }
+
+    /// The characters from `start` up to but not including `end`. Unlike a slice, the
+    /// bounds have to lie within the string.
+    #[soxmethod]
+    pub fn substring(zelf: SoxRef<SoxString>, start: SoxInt, end: SoxInt) -> SoxResult {
+        let len = zelf.value.chars().count() as i64;
+        if start.value < 0 || start.value > end.value || end.value > len {
+            return Err(Interpreter::typed_error(
+                ErrorKind::IndexError,
+                format!(
+                    "substring bounds {}..{} out of range for a string of length {}",
+                    start.value, end.value, len
+                ),
+            ));
+        }
+        Ok(zelf.slice(Some(start.value), Some(end.value)).into_ref())
+    }
+
+    /// Splits the string at every occurrence of `separator`, returning the pieces as a list.
+    #[soxmethod]
+    pub fn split(&self, separator: SoxObject) -> SoxResult {
+        let Some(separator) = separator.as_string() else {
+            return Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                "split() expects a string separator".into(),
+            ));
+        };
+        if separator.value.is_empty() {
+            return Err(Interpreter::typed_error(
+                ErrorKind::ValueError,
+                "empty separator".into(),
+            ));
+        }
+        let pieces = self
+            .value
+            .split(separator.value.as_str())
+            .map(|piece| SoxString::new(piece).into_ref())
+            .collect();
+        Ok(SoxList::new(pieces).into_ref())
+    }
+
+    /// The characters of the string, each as a string of its own.
+    #[soxmethod]
+    pub fn chars(&self) -> SoxList {
+        let chars = self
+            .value
+            .chars()
+            .map(|c| SoxString::new(c.to_string()).into_ref())
+            .collect();
+        SoxList::new(chars)
+    }
+
+    /// The empty string is false, every other string is true.
+    #[soxmethod]
+    pub fn bool(&self) -> SoxBool {
+        SoxBool::new(!self.value.is_empty())
+    }
+
     #[soxmethod]
     pub fn equals(&self, rhs: SoxObject) -> SoxBool {
         match rhs.as_string() {
@@ -28,6 +147,15 @@ impl SoxString {
             None => SoxBool::new(false),
         }
     }
+
+    /// Like `equals`, but letters that differ only in case compare equal.
+    #[soxmethod]
+    pub fn equals_ignore_case(&self, rhs: SoxObject) -> SoxBool {
+        match rhs.as_string() {
+            Some(other) => SoxBool::new(self.value.to_lowercase() == other.value.to_lowercase()),
+            None => SoxBool::new(false),
+        }
+    }
 }
 
 // impl SoxClassImpl for SoxString {
@@ -49,6 +177,7 @@ impl StaticType for SoxString {
     fn create_slots() -> SoxTypeSlot {
         SoxTypeSlot { 
             call: None,
+            new: Some(Self::construct),
             methods: Self::METHOD_DEFS,
             
         }
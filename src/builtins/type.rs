@@ -1,12 +1,15 @@
 use std::any::Any;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 use once_cell::sync::OnceCell;
-use macros::soxtype;
-use crate::builtins::exceptions::{Exception, RuntimeError};
+use macros::{soxmethod, soxtype};
+use crate::builtins::bool::SoxBool;
+use crate::builtins::builtin_function::SoxBuiltinFunction;
+use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
 use crate::builtins::function::SoxFunction;
-use crate::builtins::method::{FuncArgs, SoxMethod};
+use crate::builtins::method::{static_func, FuncArgs, SoxMethod};
 use crate::core::{
     Representable, SoxClassImpl, SoxObject, SoxObjectPayload, SoxRef, SoxResult, StaticType,
 };
@@ -18,6 +21,8 @@ pub type GenericMethod = fn(SoxObject, FuncArgs, &mut Interpreter) -> SoxResult;
 #[derive(Clone, Debug, Default)]
 pub struct SoxTypeSlot {
     pub call: Option<GenericMethod>,
+    /// Creates an object of a builtin type when the type object itself is called.
+    pub new: Option<GenericMethod>,
     pub methods: &'static [(&'static str, SoxMethod)],
 
     //pub eq: Option<GenericMethod>
@@ -25,13 +30,18 @@ pub struct SoxTypeSlot {
 
 pub type SoxAttributes = HashMap<String, SoxObject>;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SoxType {
     pub base: Option<SoxRef<SoxType>>,
     pub methods: HashMap<String, SoxMethod>,
+    /// Methods called on the type object itself rather than on its instances.
+    pub static_methods: HashMap<String, SoxMethod>,
     pub slots: SoxTypeSlot,
     pub attributes: SoxAttributes,
     pub name: Option<String>,
+    /// The result of every `find_method` lookup so far, misses included. Classes don't
+    /// change once they are defined, so entries never go stale.
+    method_cache: RefCell<HashMap<String, Option<SoxObject>>>,
 }
 
 
@@ -41,15 +51,18 @@ impl SoxType {
         name: T,
         base: Option<SoxRef<SoxType>>,
         methods: HashMap<String, SoxMethod>,
+        static_methods: HashMap<String, SoxMethod>,
         slots: SoxTypeSlot,
         attributes: SoxAttributes,
     ) -> Self {
         let typ = Self {
             base,
             methods,
+            static_methods,
             slots,
             attributes,
             name: Some(name.to_string()),
+            method_cache: Default::default(),
         };
         typ
     }
@@ -64,9 +77,11 @@ impl SoxType {
         let typ = Self {
             base,
             methods,
+            static_methods: HashMap::new(),
             slots,
             attributes,
             name: Some(name.to_string()),
+            method_cache: Default::default(),
         };
         typ
     }
@@ -79,28 +94,82 @@ impl SoxType {
         return init_method.unwrap().as_func().unwrap().arity as i32;
     }
 
+    #[soxmethod]
+    pub fn equals(zelf: &SoxRef<SoxType>, rhs: SoxObject) -> SoxBool {
+        let same_type = rhs
+            .as_type()
+            .map_or(false, |other| Rc::ptr_eq(&zelf.val, &other.val));
+        SoxBool::new(same_type)
+    }
+
+    /// Returns the nearest ancestor that is a builtin type, whose payload instances of this
+    /// class are built on.
+    pub fn builtin_base(&self) -> Option<SoxRef<SoxType>> {
+        let base = self.base.as_ref()?;
+        if base.slots.new.is_some() {
+            Some(base.clone())
+        } else {
+            base.builtin_base()
+        }
+    }
+
+    pub fn find_static_method(&self, name: &str) -> Option<SoxMethod> {
+        self.static_methods.get(name).cloned().or_else(|| {
+            self.base
+                .as_ref()
+                .and_then(|base| base.find_static_method(name))
+        })
+    }
+
+    /// Whether instances of this type have a method called `name`, either defined by a
+    /// class or provided by a builtin type.
+    pub fn defines_method(&self, name: &str) -> bool {
+        self.attributes.contains_key(name)
+            || self.methods.contains_key(name)
+            || self.base.as_ref().is_some_and(|base| base.defines_method(name))
+    }
+
     pub fn find_method(&self, name: &str) -> Option<SoxObject> {
-        self.attributes
+        if let Some(method) = self.method_cache.borrow().get(name) {
+            return method.clone();
+        }
+        let method = self
+            .attributes
             .get(name)
             .cloned()
-            .or_else(|| self.base.as_ref().and_then(|base| base.find_method(name)))
+            .or_else(|| self.base.as_ref().and_then(|base| base.find_method(name)));
+        self.method_cache
+            .borrow_mut()
+            .insert(name.to_string(), method.clone());
+        method
     }
 
     pub fn call(fo: SoxObject, args: FuncArgs, interpreter: &mut Interpreter) -> SoxResult {
         
         if let Some(to) = fo.as_type() {
-            if args.args.len() != to.arity() as usize {
-                let error = Exception::Err(RuntimeError {
-                    msg: format!(
+            if let Some(new) = to.slots.new {
+                return new(fo, args, interpreter);
+            }
+            let initializer = to.find_method("init".into());
+            if let Some(init_func) = initializer.as_ref().and_then(|v| v.as_func()) {
+                init_func.check_arity(args.args.len())?;
+            } else if args.args.len() != to.arity() as usize {
+                let error = Exception::Err(RuntimeError::new(
+                    ErrorKind::TypeError,
+                    format!(
                         "Expected {} arguments but got {}.",
                         to.arity(),
                         args.args.len()
                     ),
-                });
+                ));
                 return Err(error.into_ref());
             }
-            let instance = SoxInstance::new(to.clone());
-            let initializer = to.find_method("init".into());
+            let mut instance = SoxInstance::new(to.clone());
+            if let Some(base) = to.builtin_base() {
+                let new = base.slots.new.expect("builtin base types have a constructor");
+                let base_obj = base.to_sox_object();
+                instance.base_value = Some(new(base_obj, FuncArgs::new(vec![]), interpreter)?);
+            }
             let instance = instance.into_ref();
             let ret_val = if let Some(init_func) = initializer {
                 let func = init_func
@@ -114,14 +183,16 @@ impl SoxType {
             };
             ret_val
         } else {
-            let error = Exception::Err(RuntimeError {
-                msg: "first argument to this call method should be a type object".to_string(),
-            });
+            let error = Exception::Err(RuntimeError::new(
+                ErrorKind::TypeError,
+                "first argument to this call method should be a type object",
+            ));
             Err(error.into_ref())
         }
     }
 }
 
+/// Builtin types and classes alike print as `<type 'name'>`.
 impl Representable for SoxType {
     fn repr(&self, _i: &Interpreter) -> String {
         format!("<type '{}'>", self.name.as_ref().unwrap().to_string())
@@ -160,6 +231,7 @@ impl StaticType for SoxType {
     fn create_slots() -> SoxTypeSlot {
         SoxTypeSlot {
             call: Some(Self::call),
+            new: None,
             methods: Self::METHOD_DEFS,
         }
     }
@@ -173,6 +245,8 @@ impl StaticType for SoxType {
 pub struct SoxInstance {
     typ: SoxRef<SoxType>,
     fields: RefCell<HashMap<String, SoxObject>>,
+    /// The builtin object backing an instance of a class derived from a builtin type.
+    base_value: Option<SoxObject>,
 }
 
 impl SoxInstance {
@@ -181,9 +255,14 @@ impl SoxInstance {
         Self {
             typ: class,
             fields: RefCell::new(fields),
+            base_value: None,
         }
     }
 
+    pub fn class_ref(&self) -> SoxRef<SoxType> {
+        self.typ.clone()
+    }
+
     pub fn set(&self, name: Token, value: SoxObject) {
         self.fields.borrow_mut().insert(name.lexeme.into(), value);
     }
@@ -196,8 +275,7 @@ impl SoxInstance {
 
         if let Some(method) = inst.typ.find_method(name.lexeme.as_str()) {
             if let Some(func) = method.as_func() {
-                let bound_method = func.bind(SoxObject::TypeInstance(inst.clone()), interp);
-                return bound_method;
+                return func.property_value(SoxObject::TypeInstance(inst.clone()), interp);
             } else {
                 return Err(Interpreter::runtime_error(format!(
                     "Found property with same name, {}, but it is not a function",
@@ -206,23 +284,71 @@ impl SoxInstance {
             }
         }
 
-        Err(Interpreter::runtime_error(format!(
-            "Undefined property - {}",
-            name.lexeme
-        )))
+        if let Some(base_value) = inst.base_value.clone() {
+            let method = base_value.sox_type(interp).methods.get(&name.lexeme).cloned();
+            if let Some(method) = method {
+                let method = SoxBuiltinFunction::bound(name.lexeme.clone(), method, base_value);
+                return Ok(method.into_ref());
+            }
+        }
+
+        // as a last resort let the class compute the attribute
+        if let Some(fallback) = inst.typ.find_method("get_missing").and_then(|m| m.as_func()) {
+            let bound_method = fallback.bind(SoxObject::TypeInstance(inst.clone()), interp)?;
+            let args = FuncArgs::new(vec![interp.new_string(name.lexeme.clone())]);
+            return SoxFunction::call(bound_method, args, interp);
+        }
+
+        Err(Interpreter::typed_error(
+            ErrorKind::AttributeError,
+            format!("Undefined property - {}", name.lexeme),
+        ))
+    }
+}
+
+thread_local! {
+    /// Instances whose fields are being rendered, so that cycles are shown as `...`.
+    static REPR_STACK: RefCell<Vec<*const SoxInstance>> = const { RefCell::new(vec![]) };
+}
+
+impl SoxInstance {
+    fn fields_repr(&self, i: &Interpreter) -> String {
+        let ptr = self as *const SoxInstance;
+        if REPR_STACK.with(|stack| stack.borrow().contains(&ptr)) {
+            return "...".to_string();
+        }
+        REPR_STACK.with(|stack| stack.borrow_mut().push(ptr));
+        let fields = self.fields.borrow().clone();
+        let mut names = fields.keys().collect::<Vec<&String>>();
+        names.sort();
+        let fields = names
+            .into_iter()
+            .map(|name| format!("{}: {}", name, fields[name].nested_repr(i)))
+            .collect::<Vec<String>>();
+        REPR_STACK.with(|stack| stack.borrow_mut().pop());
+        if fields.is_empty() {
+            format!("{} {{}}", self.name())
+        } else {
+            format!("{} {{ {} }}", self.name(), fields.join(", "))
+        }
+    }
+
+    fn name(&self) -> String {
+        self.typ
+            .name
+            .as_ref()
+            .unwrap_or(&"Unknown type".to_string())
+            .to_string()
     }
 }
 
 impl Representable for SoxInstance {
-    fn repr(&self, _i: &Interpreter) -> String {
-        format!(
-            "<{} instance>",
-            self.typ
-                .name
-                .as_ref()
-                .unwrap_or(&"Unknown type".to_string())
-                .to_string()
-        )
+    fn repr(&self, i: &Interpreter) -> String {
+        if i.debug_repr {
+            self.fields_repr(i)
+        } else {
+            format!("<{} instance>", self.name())
+        }
     }
 }
 impl SoxObjectPayload for SoxInstance {
@@ -247,3 +373,45 @@ impl SoxObjectPayload for SoxInstance {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::builtins::int::SoxInt;
+    use crate::builtins::r#type::SoxType;
+    use crate::core::{SoxObjectPayload, SoxRef};
+
+    #[test]
+    fn test_find_method_caches_lookups() {
+        let mut attributes = HashMap::new();
+        attributes.insert("method".to_string(), SoxInt::new(1).into_ref());
+        let base = SoxRef::new(SoxType::new(
+            "Base",
+            None,
+            HashMap::new(),
+            Default::default(),
+            attributes,
+        ));
+        let derived = SoxType::new(
+            "Derived",
+            Some(base.clone()),
+            HashMap::new(),
+            Default::default(),
+            HashMap::new(),
+        );
+
+        assert!(derived.find_method("method").is_some());
+        assert!(derived.find_method("missing").is_none());
+        assert!(base.method_cache.borrow().contains_key("method"));
+
+        // later lookups are answered by the derived type without walking to its base
+        base.method_cache.borrow_mut().clear();
+        for _ in 0..100 {
+            let method = derived.find_method("method").and_then(|m| m.as_int());
+            assert_eq!(method.map(|m| m.value), Some(1));
+            assert!(derived.find_method("missing").is_none());
+        }
+        assert!(base.method_cache.borrow().is_empty());
+    }
+}
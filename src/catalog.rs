@@ -1,6 +1,8 @@
 use crate::builtins::r#type::SoxType;
-use crate::builtins::{bool, exceptions, float, function, int, none, r#type, string};
-use crate::core::StaticType;
+use crate::builtins::{
+    bool, builtin_function, exceptions, float, function, int, list, none, r#type, string,
+};
+use crate::core::{SoxRef, StaticType};
 
 #[derive(Debug)]
 pub struct TypeLibrary {
@@ -11,12 +13,15 @@ pub struct TypeLibrary {
     pub none_type: &'static SoxType,
     pub exception_type: &'static SoxType,
     pub func_type: &'static SoxType,
+    pub builtin_func_type: &'static SoxType,
+    pub list_type: &'static SoxType,
     pub type_type: &'static SoxType,
+    objects: Vec<SoxRef<SoxType>>,
 }
 
 impl TypeLibrary {
     pub fn init() -> Self {
-        Self {
+        let mut library = Self {
             bool_type: bool::SoxBool::init_builtin_type(),
             float_type: float::SoxFloat::init_builtin_type(),
             int_type: int::SoxInt::init_builtin_type(),
@@ -24,7 +29,59 @@ impl TypeLibrary {
             none_type: none::SoxNone::init_builtin_type(),
             exception_type: exceptions::Exception::init_builtin_type(),
             func_type: function::SoxFunction::init_builtin_type(),
+            builtin_func_type: builtin_function::SoxBuiltinFunction::init_builtin_type(),
+            list_type: list::SoxList::init_builtin_type(),
             type_type: r#type::SoxType::init_builtin_type(),
-        }
+            objects: vec![],
+        };
+        library.objects = library
+            .builtin_types()
+            .into_iter()
+            .map(|typ| SoxRef::new(typ.clone()))
+            .collect();
+        library
+    }
+
+    fn builtin_types(&self) -> [&'static SoxType; 10] {
+        [
+            self.bool_type,
+            self.float_type,
+            self.int_type,
+            self.str_type,
+            self.none_type,
+            self.exception_type,
+            self.func_type,
+            self.builtin_func_type,
+            self.list_type,
+            self.type_type,
+        ]
+    }
+
+    /// Looks up a builtin type by the name scripts know it under, e.g. `int` or `string`.
+    pub fn get_by_name(&self, name: &str) -> Option<&'static SoxType> {
+        self.builtin_types()
+            .into_iter()
+            .find(|typ| typ.name.as_deref() == Some(name))
+    }
+
+    /// Returns the object scripts see for a builtin type. The same object is handed out on
+    /// every call, so type objects can be compared by identity.
+    pub fn type_object(&self, typ: &SoxType) -> Option<SoxRef<SoxType>> {
+        self.objects.iter().find(|obj| obj.name == typ.name).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+
+    #[test]
+    fn test_get_by_name() {
+        let types = Interpreter::new().types;
+        let int_type = types.get_by_name("int").unwrap();
+        assert!(std::ptr::eq(int_type, types.int_type));
+        let str_type = types.get_by_name("string").unwrap();
+        assert!(std::ptr::eq(str_type, types.str_type));
+        assert!(types.get_by_name("dict").is_none());
     }
 }
@@ -7,10 +7,12 @@ use std::rc::Rc;
 pub use once_cell::sync::{Lazy, OnceCell};
 
 use crate::builtins::bool::SoxBool;
+use crate::builtins::builtin_function::SoxBuiltinFunction;
 use crate::builtins::exceptions::Exception;
 use crate::builtins::float::SoxFloat;
 use crate::builtins::function::SoxFunction;
 use crate::builtins::int::SoxInt;
+use crate::builtins::list::SoxList;
 use crate::builtins::method::{FuncArgs, SoxMethod};
 use crate::builtins::none::SoxNone;
 use crate::builtins::r#type::{SoxInstance, SoxType, SoxTypeSlot};
@@ -24,6 +26,8 @@ pub enum SoxObject {
     Float(SoxRef<SoxFloat>),
     Boolean(SoxRef<SoxBool>),
     Function(SoxRef<SoxFunction>),
+    BuiltinFunction(SoxRef<SoxBuiltinFunction>),
+    List(SoxRef<SoxList>),
     Exception(SoxRef<Exception>),
     None(SoxRef<SoxNone>),
     Type(SoxRef<SoxType>),
@@ -38,6 +42,8 @@ impl SoxObject {
             SoxObject::Float(v) => v.class(i),
             SoxObject::Boolean(v) => v.class(i),
             SoxObject::Function(v) => v.class(i),
+            SoxObject::BuiltinFunction(v) => v.class(i),
+            SoxObject::List(v) => v.class(i),
             SoxObject::Exception(v) => v.class(i),
             SoxObject::None(v) => v.class(i),
             SoxObject::Type(v) => v.class(i),
@@ -53,6 +59,8 @@ impl SoxObject {
             SoxObject::Float(v) => v.repr(i),
             SoxObject::Boolean(v) => v.repr(i),
             SoxObject::Function(v) => v.repr(i),
+            SoxObject::BuiltinFunction(v) => v.repr(i),
+            SoxObject::List(v) => v.repr(i),
             SoxObject::Exception(v) => v.repr(i),
             SoxObject::None(v) => v.repr(i),
             SoxObject::Type(v) => v.repr(i),
@@ -61,7 +69,25 @@ impl SoxObject {
         val
     }
 
-    pub fn try_into_rust_bool(&self, i: &Interpreter) -> bool {
+    /// The repr used when the object is shown inside another one, such as a list element;
+    /// strings are quoted so that they can be told apart from other values.
+    pub fn nested_repr(&self, i: &Interpreter) -> String {
+        match self {
+            SoxObject::String(v) => v.quoted(),
+            _ => self.repr(i),
+        }
+    }
+
+    pub fn try_into_rust_bool(&self, i: &mut Interpreter) -> bool {
+        // primitives have a fixed truth value, so skip the method lookup for them
+        match self {
+            SoxObject::Boolean(v) => return v.value,
+            SoxObject::None(_) => return false,
+            SoxObject::Int(v) => return v.value != 0,
+            SoxObject::Float(v) => return v.value != 0.0,
+            SoxObject::String(v) => return !v.value.is_empty(),
+            _ => {}
+        }
         let typ = self.sox_type(i);
 
         let truth_val = if let Some(meth) = typ.methods.get("bool") {
@@ -121,6 +147,20 @@ impl SoxObject {
         }
     }
 
+    pub fn as_builtin_func(&self) -> Option<SoxRef<SoxBuiltinFunction>> {
+        match self {
+            SoxObject::BuiltinFunction(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<SoxRef<SoxList>> {
+        match self {
+            SoxObject::List(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+
     pub fn as_exception(&self) -> Option<SoxRef<Exception>> {
         match self {
             SoxObject::Exception(v) => Some(v.clone()),
@@ -151,6 +191,7 @@ pub trait SoxNativeFunction {
 
 pub trait SoxClassImpl {
     const METHOD_DEFS: &'static [(&'static str, SoxMethod)];
+    const STATIC_METHOD_DEFS: &'static [(&'static str, SoxMethod)] = &[];
 }
 
 pub trait StaticType {
@@ -174,6 +215,7 @@ pub trait StaticType {
         Self: SoxClassImpl,
     {
         let methods = Self::METHOD_DEFS;
+        let static_methods = Self::STATIC_METHOD_DEFS;
         let slots = Self::create_slots();
         SoxType::new_static_type(
             Self::NAME,
@@ -182,6 +224,10 @@ pub trait StaticType {
                 .iter()
                 .map(move |v| (v.0.to_string(), v.1.clone()))
                 .collect::<HashMap<String, SoxMethod>>(),
+            static_methods
+                .iter()
+                .map(move |v| (v.0.to_string(), v.1.clone()))
+                .collect::<HashMap<String, SoxMethod>>(),
             slots,
             Default::default(),
         )
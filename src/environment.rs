@@ -1,4 +1,4 @@
-use crate::builtins::exceptions::{Exception, RuntimeError};
+use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
 use crate::core::{SoxObject, SoxObjectPayload, SoxResult};
 use slotmap::{DefaultKey, SlotMap};
 use std::collections::HashMap;
@@ -9,9 +9,23 @@ use std::rc::Rc;
 pub(crate) type EnvKey = (String, usize, usize);
 pub type EnvRef = Rc<DefaultKey>;
 
+/// Storage strategy used for namespace bindings.
+///
+/// Bindings always live in a `Vec` so resolved variables can be read by index. In `Map`
+/// mode each namespace also keeps a name to index map, so lookups by name (globals and
+/// programs run without the resolver) don't have to scan the bindings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StoreMode {
+    #[default]
+    Vec,
+    Map,
+}
+
 #[derive(Clone, Debug)]
 pub struct Namespace {
     pub bindings: Vec<(String, SoxObject)>,
+    index: HashMap<String, usize>,
+    store_mode: StoreMode,
 }
 
 impl Default for Namespace {
@@ -21,8 +35,15 @@ impl Default for Namespace {
 }
 impl Namespace {
     pub(crate) fn new() -> Self {
-        let bindings = vec![];
-        Self { bindings }
+        Namespace::with_store_mode(StoreMode::default())
+    }
+
+    pub(crate) fn with_store_mode(store_mode: StoreMode) -> Self {
+        Self {
+            bindings: vec![],
+            index: HashMap::new(),
+            store_mode,
+        }
     }
 
     pub(crate) fn define<T: ToString + Display>(
@@ -30,10 +51,23 @@ impl Namespace {
         key: T,
         value: SoxObject,
     ) -> SoxResult<()> {
-        self.bindings.push((key.to_string(), value));
+        let key = key.to_string();
+        if self.store_mode == StoreMode::Map {
+            self.index.insert(key.clone(), self.bindings.len());
+        }
+        self.bindings.push((key, value));
         Ok(())
     }
 
+    /// Returns the index of the latest binding for `name`; a name defined more than once in
+    /// the same namespace is shadowed by its most recent definition.
+    pub(crate) fn lookup(&self, name: &str) -> Option<usize> {
+        match self.store_mode {
+            StoreMode::Vec => self.bindings.iter().rposition(|(key, _)| key == name),
+            StoreMode::Map => self.index.get(name).copied(),
+        }
+    }
+
     pub(crate) fn assign(&mut self, key: &EnvKey, value: SoxObject) -> SoxResult<()> {
         let (name, _, binding_idx) = key;
         let mut binding = self.get_binding_mut(*binding_idx);
@@ -53,9 +87,10 @@ impl Namespace {
         if let Some(v) = binding {
             Ok(v.1.clone())
         } else {
-            Err(Exception::Err(RuntimeError {
-                msg: format!("NameError: name '{}' is not defined", name),
-            })
+            Err(Exception::Err(RuntimeError::new(
+                ErrorKind::NameError,
+                format!("name '{}' is not defined", name),
+            ))
             .into_ref())
         }
     }
@@ -78,6 +113,7 @@ pub struct Environment {
     pub active: EnvRef,
     pub global: EnvRef,
     pub env_link: HashMap<EnvRef, EnvRef>,
+    pub store_mode: StoreMode,
 }
 
 impl Environment {
@@ -87,8 +123,12 @@ impl Environment {
         env_ref
     }
     pub fn new() -> Environment {
+        Environment::with_store_mode(StoreMode::default())
+    }
+
+    pub fn with_store_mode(store_mode: StoreMode) -> Environment {
         let mut envs = SlotMap::new();
-        let global_env = Namespace::new();
+        let global_env = Namespace::with_store_mode(store_mode);
         let global_env_ref = envs.insert(global_env);
         let global_env_ref = Rc::new(global_env_ref);
         //let env_rc = SecondaryMap::new();
@@ -97,6 +137,7 @@ impl Environment {
             active: global_env_ref.clone(),
             global: global_env_ref,
             env_link: Default::default(),
+            store_mode,
             //env_rc,
         }
     }
@@ -107,7 +148,7 @@ impl Environment {
     }
 
     fn create_environment(&mut self, enclosing_env_ref: EnvRef) -> EnvRef {
-        let new_env = Namespace::new();
+        let new_env = Namespace::with_store_mode(self.store_mode);
         let new_env_ref = self.stack_new_env(new_env);
         self.env_link.insert(new_env_ref.clone(), enclosing_env_ref);
 
@@ -132,14 +173,25 @@ impl Environment {
         let _ = ns.define(key, value);
     }
 
+    /// Names bound in the global namespace.
+    pub fn global_names(&self) -> Vec<String> {
+        let global_namespace = self.envs.get(*self.global).unwrap();
+        global_namespace
+            .bindings
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
     pub fn get_from_global_scope(&self, key: String) -> SoxResult {
         let key_string = key.to_string();
         let global_namespace = self.envs.get(*self.global).unwrap();
-        match global_namespace.bindings.iter().find(|v| v.0 == key_string) {
-            Some(v) => Ok(v.1.clone()),
-            None => Err(Exception::Err(RuntimeError {
-                msg: format!("NameError: name '{key_string}' is not defined."),
-            })
+        match global_namespace.lookup(&key_string) {
+            Some(idx) => Ok(global_namespace.bindings[idx].1.clone()),
+            None => Err(Exception::Err(RuntimeError::new(
+                ErrorKind::NameError,
+                format!("name '{key_string}' is not defined."),
+            ))
             .into_ref()),
         }
     }
@@ -158,9 +210,10 @@ impl Environment {
                     namespace = self.envs.get_mut(**parent_ns).unwrap();
                 }
                 None => {
-                    return Err(Exception::Err(RuntimeError {
-                        msg: format!("NameError: name '{:?}' is not defined", name),
-                    })
+                    return Err(Exception::Err(RuntimeError::new(
+                        ErrorKind::NameError,
+                        format!("name '{:?}' is not defined", name),
+                    ))
                     .into_ref())
                 }
             }
@@ -174,21 +227,16 @@ impl Environment {
         let key_string = key.to_string();
         let mut current_ns_key = Some(self.active.clone());
         while let Some(namespace_key) = current_ns_key {
-            let namespace = self.envs.get_mut(*namespace_key).unwrap();
-            if let Some(value) = namespace.bindings.iter_mut().find_map(|(k, v)| {
-                if *k == key_string {
-                    Some(v.clone())
-                } else {
-                    None
-                }
-            }) {
-                return Ok(value);
+            let namespace = self.envs.get(*namespace_key).unwrap();
+            if let Some(idx) = namespace.lookup(&key_string) {
+                return Ok(namespace.bindings[idx].1.clone());
             }
             current_ns_key = self.env_link.get(&namespace_key).cloned();
         }
-        Err(Exception::Err(RuntimeError {
-            msg: format!("NameError: name '{key_string}' is not defined"),
-        })
+        Err(Exception::Err(RuntimeError::new(
+            ErrorKind::NameError,
+            format!("name '{key_string}' is not defined"),
+        ))
         .into_ref())
     }
 
@@ -201,15 +249,16 @@ impl Environment {
         let mut ns_key = Some(self.active.clone());
         while let Some(nsk) = ns_key {
             let ns = self.envs.get_mut(*nsk).unwrap();
-            if let Some(v) = ns.bindings.iter_mut().find(|v| v.0 == key_string) {
-                v.1 = value;
+            if let Some(idx) = ns.lookup(&key_string) {
+                ns.bindings[idx].1 = value;
                 return Ok(());
             }
             ns_key = self.env_link.get(&nsk).cloned();
         }
-        Err(Exception::Err(RuntimeError {
-            msg: format!("NameError: name '{key_string}' is not defined."),
-        })
+        Err(Exception::Err(RuntimeError::new(
+            ErrorKind::NameError,
+            format!("name '{key_string}' is not defined."),
+        ))
         .into_ref())
     }
 
@@ -220,14 +269,15 @@ impl Environment {
     ) -> SoxResult<()> {
         let key_string = key.to_string();
         let global_ns = self.envs.get_mut(*self.global).unwrap();
-        if let Some(v) = global_ns.bindings.iter_mut().find(|v| v.0 == key_string) {
-            v.1 = value;
+        if let Some(idx) = global_ns.lookup(&key_string) {
+            global_ns.bindings[idx].1 = value;
             return Ok(());
         }
 
-        Err(Exception::Err(RuntimeError {
-            msg: format!("NameError: name '{key_string}' is not defined."),
-        })
+        Err(Exception::Err(RuntimeError::new(
+            ErrorKind::NameError,
+            format!("name '{key_string}' is not defined."),
+        ))
         .into_ref())
     }
 
@@ -243,6 +293,22 @@ impl Environment {
         Ok(())
     }
 
+    /// Removes the namespace behind `env_ref` if nothing but its link to the enclosing
+    /// namespace refers to it, then does the same for the enclosing namespaces it kept alive.
+    pub fn release(&mut self, env_ref: EnvRef) {
+        let mut env_ref = env_ref;
+        // one reference is `env_ref` itself, the other is the key in `env_link`
+        while Rc::strong_count(&env_ref) == 2 {
+            match self.env_link.remove(&env_ref) {
+                Some(parent) => {
+                    self.envs.remove(*env_ref);
+                    env_ref = parent;
+                }
+                None => break,
+            }
+        }
+    }
+
     pub fn pop(&mut self) -> SoxResult<()> {
         let (active, parent) = (
             self.active.clone(),
@@ -259,3 +325,45 @@ impl Environment {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::StoreMode;
+    use crate::interpreter::Interpreter;
+
+    const SOURCE: &str = r#"
+let total = 0;
+let total = 1;
+def counter() {
+    let count = 0;
+    def increment() {
+        count = count + 1;
+        return count;
+    }
+    return increment;
+}
+class Box {
+    init(value) {
+        this.value = value;
+    }
+}
+let next = counter();
+next();
+for (let i = 0; i < 5; i = i + 1) {
+    total = total + i;
+}
+total + next() + Box(10).value;
+"#;
+
+    fn eval_with(store_mode: StoreMode) -> i64 {
+        let mut interpreter = Interpreter::with_store_mode(store_mode);
+        let value = interpreter.eval(SOURCE).unwrap();
+        value.as_int().unwrap().value
+    }
+
+    #[test]
+    fn test_store_modes_agree() {
+        assert_eq!(eval_with(StoreMode::Vec), 23);
+        assert_eq!(eval_with(StoreMode::Map), 23);
+    }
+}
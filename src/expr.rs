@@ -1,16 +1,41 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::stmt::Stmt;
 use crate::token::{Literal, Token};
 
+/// Identifies a reference to a variable (a use of a name, `this` or `super`), so the
+/// resolver's results can be keyed on it instead of on cloned tokens. Ids are unique
+/// across every program parsed by the process, as an interpreter keeps the resolutions
+/// of earlier runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VarRef(u32);
+
+static NEXT_VAR_REF: AtomicU32 = AtomicU32::new(0);
+
+impl VarRef {
+    pub fn next() -> Self {
+        VarRef(NEXT_VAR_REF.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Expr {
     Assign {
         name: Token,
         value: Box<Expr>,
+        var: VarRef,
     },
     Binary {
         left: Box<Expr>,
         operator: Token,
         right: Box<Expr>,
     },
+    /// A block in expression position, evaluating to its trailing expression (or `None`
+    /// when the block ends with a statement).
+    Block {
+        statements: Vec<Stmt>,
+        value: Option<Box<Expr>>,
+    },
     Call {
         callee: Box<Expr>,
         paren: Token,
@@ -23,28 +48,54 @@ pub enum Expr {
     Grouping {
         expr: Box<Expr>,
     },
+    /// A subscript read, `object[index]`.
+    Index {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+    },
+    List {
+        elements: Vec<Expr>,
+    },
     Literal {
         value: Literal,
     },
     Variable {
         name: Token,
+        var: VarRef,
     },
     Logical {
         left: Box<Expr>,
         operator: Token,
         right: Box<Expr>,
     },
+    /// A slice of a list or string, `object[start:end]`; either bound may be left out.
+    Slice {
+        object: Box<Expr>,
+        bracket: Token,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+    },
     Set {
         object: Box<Expr>,
         name: Token,
         value: Box<Expr>,
     },
+    /// A subscript assignment, `object[index] = value`.
+    SetIndex {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+        value: Box<Expr>,
+    },
     Super {
         keyword: Token,
         method: Token,
+        var: VarRef,
     },
     This {
         keyword: Token,
+        var: VarRef,
     },
     Unary {
         operator: Token,
@@ -57,7 +108,9 @@ impl Expr {
         match self {
             Expr::Assign { .. } => visitor.visit_assign_expr(&self),
             Expr::Binary { .. } => visitor.visit_binary_expr(&self),
+            Expr::Block { .. } => visitor.visit_block_expr(self),
             Expr::Grouping { .. } => visitor.visit_grouping_expr(&self),
+            Expr::List { .. } => visitor.visit_list_expr(&self),
             Expr::Literal { .. } => visitor.visit_literal_expr(&self),
             Expr::Unary { .. } => visitor.visit_unary_expr(&self),
             Expr::Variable { .. } => visitor.visit_variable_expr(&self),
@@ -65,19 +118,49 @@ impl Expr {
             Expr::Call { .. } => visitor.visit_call_expr(&self),
             Expr::Get { .. } => visitor.visit_get_expr(&self),
             Expr::Set { .. } => visitor.visit_set_expr(&self),
+            Expr::Index { .. } => visitor.visit_index_expr(self),
+            Expr::SetIndex { .. } => visitor.visit_set_index_expr(self),
+            Expr::Slice { .. } => visitor.visit_slice_expr(self),
             Expr::This { .. } => visitor.visit_this_expr(&self),
             Expr::Super { .. } => visitor.visit_super_expr(self),
         }
     }
 }
 
+impl Expr {
+    /// The line of the token that identifies this expression, for locating runtime errors.
+    /// Expressions without a token of their own report errors at a nested expression.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Expr::Assign { name, .. }
+            | Expr::Get { name, .. }
+            | Expr::Set { name, .. }
+            | Expr::Variable { name, .. } => Some(name.line),
+            Expr::Binary { operator, .. }
+            | Expr::Logical { operator, .. }
+            | Expr::Unary { operator, .. } => Some(operator.line),
+            Expr::Call { paren, .. } => Some(paren.line),
+            Expr::Index { bracket, .. }
+            | Expr::SetIndex { bracket, .. }
+            | Expr::Slice { bracket, .. } => Some(bracket.line),
+            Expr::Super { keyword, .. } | Expr::This { keyword, .. } => Some(keyword.line),
+            Expr::Block { .. }
+            | Expr::Grouping { .. }
+            | Expr::List { .. }
+            | Expr::Literal { .. } => None,
+        }
+    }
+}
+
 pub trait ExprVisitor {
     type T;
 
     fn visit_assign_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_literal_expr(&mut self, expr: &Expr) -> Self::T;
+    fn visit_list_expr(&mut self, expr: &Expr) -> Self::T;
 
     fn visit_binary_expr(&mut self, expr: &Expr) -> Self::T;
+    fn visit_block_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_grouping_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_unary_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_logical_expr(&mut self, expr: &Expr) -> Self::T;
@@ -85,6 +168,9 @@ pub trait ExprVisitor {
     fn visit_call_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_get_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_set_expr(&mut self, expr: &Expr) -> Self::T;
+    fn visit_index_expr(&mut self, expr: &Expr) -> Self::T;
+    fn visit_set_index_expr(&mut self, expr: &Expr) -> Self::T;
+    fn visit_slice_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_this_expr(&mut self, expr: &Expr) -> Self::T;
     fn visit_super_expr(&mut self, expr: &Expr) -> Self::T;
 }
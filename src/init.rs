@@ -1,62 +1,385 @@
+use crate::core::SoxObject;
+use crate::environment::StoreMode;
 use crate::interpreter::Interpreter;
 use crate::lexer::Lexer;
-use crate::parser::Parser;
-use crate::resolver::Resolver;
-use std::io::Write;
+use crate::parser::{Parser, SyntaxError};
+use crate::resolver::{Resolver, ResolverError, ResolverWarning};
+use crate::stmt::Stmt;
+use crate::token::Token;
+use crate::token_type::TokenType;
+use std::io::{BufRead, Write};
 use std::{fs, io};
 
-pub fn run_file(file_path: String) {
+/// Diagnostics produced when a program fails to compile or stops with an uncaught error.
+#[derive(Clone, Debug)]
+pub enum RunError {
+    Syntax(Vec<SyntaxError>),
+    Resolution(ResolverError),
+    Warnings(Vec<ResolverWarning>),
+    Runtime(String),
+}
+
+/// How warnings collected while resolving a program are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WarningMode {
+    /// Print warnings to stderr and run the program anyway.
+    #[default]
+    Print,
+    /// Treat warnings as errors and don't run the program.
+    Error,
+}
+
+pub type RunResult = Result<(), RunError>;
+
+/// ANSI escape sequences the REPL uses to color echoed values by type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub number: String,
+    pub string: String,
+    pub boolean: String,
+    pub none: String,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self {
+            number: "\x1b[33m".to_string(),
+            string: "\x1b[32m".to_string(),
+            boolean: "\x1b[35m".to_string(),
+            none: "\x1b[90m".to_string(),
+        }
+    }
+}
+
+impl ColorScheme {
+    const RESET: &'static str = "\x1b[0m";
+
+    /// Colors `text`, the repr of `value`, according to the value's type. Values of other
+    /// types are returned as is.
+    fn paint(&self, value: &SoxObject, text: String) -> String {
+        let color = match value {
+            SoxObject::Int(_) | SoxObject::Float(_) => &self.number,
+            SoxObject::String(_) => &self.string,
+            SoxObject::Boolean(_) => &self.boolean,
+            SoxObject::None(_) => &self.none,
+            _ => return text,
+        };
+        format!("{}{}{}", color, text, Self::RESET)
+    }
+}
+
+/// Formats a value echoed by the REPL, colored when a color scheme is given.
+pub(crate) fn format_echo(
+    value: &SoxObject,
+    interpreter: &Interpreter,
+    colors: Option<&ColorScheme>,
+) -> String {
+    let text = value.repr(interpreter);
+    match colors {
+        Some(colors) => colors.paint(value, text),
+        None => text,
+    }
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let lines = match self {
+            RunError::Syntax(errors) => errors.iter().map(|e| e.to_string()).collect(),
+            RunError::Resolution(e) => vec![e.to_string()],
+            RunError::Warnings(warnings) => warnings.iter().map(|w| w.to_string()).collect(),
+            RunError::Runtime(msg) => vec![msg.to_string()],
+        };
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Runs the script at `file_path` and returns the repr of its last statement's value for
+/// the CLI to echo, or `None` when that value is None.
+pub fn run_file(
+    file_path: String,
+    warning_mode: WarningMode,
+) -> Result<Option<String>, RunError> {
     let contents =
         fs::read_to_string(file_path).expect("Failed to read content of provided file path");
-    run(contents, true)
+    let mut interpreter = Interpreter::new();
+    match run_in(&mut interpreter, contents.as_str(), true, warning_mode)? {
+        SoxObject::None(_) => Ok(None),
+        value => Ok(Some(value.repr(&interpreter))),
+    }
 }
 
-pub fn run_prompt() {
-    let stdin = io::stdin();
+/// Runs the interactive prompt. Echoed values are colored with `colors` when it is set;
+/// callers should pass `None` when stdout isn't a terminal.
+pub fn run_prompt(colors: Option<ColorScheme>) {
     let mut interpreter = Interpreter::new();
     println!("Welcome to sox");
 
     loop {
-        print!(">>> ");
-        let _ = io::stdout().flush();
-        let mut buffer = String::new();
-        stdin.read_line(&mut buffer).unwrap();
-        if buffer.is_empty() {
+        let input = read_source(&mut io::stdin().lock(), &mut io::stdout());
+        let Some(buffer) = input.unwrap() else {
             break;
+        };
+        match interpreter.eval(buffer.as_str()) {
+            Ok(SoxObject::None(_)) => {}
+            Ok(value) => println!("{}", format_echo(&value, &interpreter, colors.as_ref())),
+            Err(error) => println!("{}", interpreter.error_report(&error)),
         }
-        let tokens = Lexer::lex(buffer.as_str());
-        let mut parser = Parser::new(tokens);
-        let ast = parser.parse();
-        if let Ok(ast) = ast {
-            interpreter.interpret(&ast);
-        } else {
-            println!("Error - {:?}", ast.err().unwrap());
+    }
+}
+
+/// Reads one REPL entry. Lines are collected, with a `... ` prompt for each continuation
+/// line, for as long as the input so far is incomplete; a blank line stops collecting
+/// so the incomplete input is evaluated and its error shown. Returns `None` when the
+/// reader is exhausted before anything was entered.
+pub(crate) fn read_source<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<Option<String>> {
+    let mut buffer = String::new();
+    loop {
+        write!(writer, "{}", if buffer.is_empty() { ">>> " } else { "... " })?;
+        writer.flush()?;
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(if buffer.is_empty() { None } else { Some(buffer) });
         }
+        let blank = line.trim().is_empty();
+        buffer.push_str(&line);
+        if blank || !is_incomplete(&buffer) {
+            return Ok(Some(buffer));
+        }
+    }
+}
+
+/// Whether `source` is the beginning of a longer input: it has unclosed brackets or the
+/// parser ran out of tokens.
+fn is_incomplete(source: &str) -> bool {
+    let tokens = Lexer::lex(source).collect::<Vec<Token>>();
+    let depth = tokens.iter().fold(0, |depth, token| match token.token_type {
+        TokenType::LeftBrace | TokenType::LeftParen | TokenType::LeftSqb => depth + 1,
+        TokenType::RightBrace | TokenType::RightParen | TokenType::RightSqb => depth - 1,
+        _ => depth,
+    });
+    if depth > 0 {
+        return true;
+    }
+    match Parser::new(tokens.into_iter()).parse() {
+        Ok(_) => false,
+        Err(errors) => errors.iter().any(|e| e.at_eof()),
     }
 }
 
-pub fn run(source: String, enable_var_resolution: bool) {
-    let tokens = Lexer::lex(source.as_str());
+/// Lexes, parses and (unless `enable_var_resolution` is false) resolves `source`, recording
+/// the resolved locals on `interpreter`, and returns the statements ready to execute, e.g.
+/// with `Interpreter::run_collecting`. Warnings are printed to stderr or returned as an
+/// error depending on `warning_mode`.
+pub fn prepare(
+    interpreter: &mut Interpreter,
+    source: &str,
+    enable_var_resolution: bool,
+    warning_mode: WarningMode,
+) -> Result<Vec<Stmt>, RunError> {
+    let tokens = Lexer::lex(source);
     let mut parser = Parser::new(tokens);
-    let mut var_resolver = Resolver::new();
+    let ast = parser.parse().map_err(RunError::Syntax)?;
+
+    if enable_var_resolution {
+        let mut var_resolver = Resolver::new();
+        var_resolver.declare_globals(interpreter.environment.global_names());
+        let locals = var_resolver.resolve(&ast).map_err(RunError::Resolution)?;
+        let warnings = var_resolver.warnings();
+        if !warnings.is_empty() && warning_mode == WarningMode::Error {
+            return Err(RunError::Warnings(warnings.to_vec()));
+        }
+        for warning in warnings {
+            eprintln!("{}", warning);
+        }
+        interpreter.locals.extend(locals);
+    }
+    Ok(ast)
+}
 
-    let ast = parser.parse();
+/// Lexes, parses, resolves and executes `source` with a fresh interpreter.
+///
+/// This is the entry point used by the CLI, the benchmarks and embedding hosts that want a
+/// fresh interpreter per program; hosts that keep state between programs use `run_in`.
+/// Nothing is printed apart from the program's own output: syntax, resolution and uncaught
+/// runtime errors are all returned so callers can report them. `enable_var_resolution`
+/// skips the resolver pass entirely, `store_mode` selects how namespace bindings are stored
+/// and `warning_mode` decides whether resolver warnings are printed or abort the run.
+pub fn run(
+    source: String,
+    enable_var_resolution: bool,
+    store_mode: StoreMode,
+    warning_mode: WarningMode,
+) -> RunResult {
+    let mut interpreter = Interpreter::with_store_mode(store_mode);
+    run_in(
+        &mut interpreter,
+        source.as_str(),
+        enable_var_resolution,
+        warning_mode,
+    )
+    .map(|_| ())
+}
 
-    let mut interpreter = Interpreter::new();
+/// Like `run`, but executes `source` against an existing interpreter so that successive
+/// chunks of code share its globals, e.g. the cells of a notebook. Returns the value of the
+/// last statement.
+pub fn run_in(
+    interpreter: &mut Interpreter,
+    source: &str,
+    enable_var_resolution: bool,
+    warning_mode: WarningMode,
+) -> Result<SoxObject, RunError> {
+    let ast = prepare(interpreter, source, enable_var_resolution, warning_mode)?;
+    let result = interpreter.execute_program(&ast);
+    // the caller reports errors, so the program's output has to be out before it does
+    let _ = interpreter.output.flush();
+    result.map_err(|error| RunError::Runtime(interpreter.error_report(&error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::StoreMode;
+    use std::io::Cursor;
+
+    use crate::init::{
+        format_echo, read_source, run, run_in, ColorScheme, RunError, WarningMode,
+    };
+    use crate::interpreter::Interpreter;
+
+    #[test]
+    fn test_run_valid_program() {
+        let source = "let a = 1;\nprint a + 1;".to_string();
+        assert!(run(source, true, StoreMode::Vec, WarningMode::Print).is_ok());
+    }
 
-    if ast.is_ok() {
-        if enable_var_resolution {
-            let resolved_data = var_resolver.resolve(&ast.as_ref().unwrap());
-            match resolved_data {
-                Ok(data) => {
-                    interpreter.locals = data;
-                    interpreter.interpret(&ast.unwrap())
-                }
-                Err(e) => {
-                    println!("{}", e.to_string());
-                }
-            }
-            //interpreter._locals = resolved_data.unwrap();
+    #[test]
+    fn test_run_in_shares_globals_between_chunks() {
+        let mut interpreter = Interpreter::new();
+        let first = "let greeting = \"hello\";\ndef greet(name) { return greeting + \" \" + name; }";
+        assert!(run_in(&mut interpreter, first, true, WarningMode::Print).is_ok());
+
+        let second = "let message = greet(\"sox\");";
+        assert!(run_in(&mut interpreter, second, true, WarningMode::Print).is_ok());
+        let message = interpreter.eval("message;").unwrap();
+        assert_eq!(message.repr(&interpreter), "hello sox");
+
+        let result = run_in(&mut interpreter, "print missing;", true, WarningMode::Print);
+        assert!(matches!(result, Err(RunError::Resolution(_))));
+    }
+
+    #[test]
+    fn test_run_in_returns_the_value_of_the_last_statement() {
+        let mut interpreter = Interpreter::new();
+        let value = run_in(&mut interpreter, "let a = 1;\na + 2;", true, WarningMode::Print);
+        assert_eq!(value.unwrap().repr(&interpreter), "3");
+    }
+
+    #[test]
+    fn test_run_reports_syntax_errors() {
+        let source = "let a = ;".to_string();
+        let result = run(source, true, StoreMode::Vec, WarningMode::Print);
+        assert!(matches!(result, Err(RunError::Syntax(errors)) if errors.len() == 1));
+    }
+
+    #[test]
+    fn test_run_reports_resolution_errors() {
+        let source = "return 1;".to_string();
+        let result = run(source, true, StoreMode::Vec, WarningMode::Print);
+        assert!(matches!(result, Err(RunError::Resolution(_))));
+    }
+
+    #[test]
+    fn test_run_reports_runtime_errors() {
+        let source = "print 1;\nlet a = 1 + true;\nprint 2;".to_string();
+        let result = run(source, true, StoreMode::Vec, WarningMode::Print);
+        assert!(matches!(result, Err(RunError::Runtime(msg)) if msg.starts_with("TypeError")));
+    }
+
+    #[test]
+    fn test_override_without_superclass_fails_without_resolution() {
+        let source = "class A {\n  override f() {}\n}".to_string();
+        let result = run(source, false, StoreMode::Vec, WarningMode::Print);
+        let Err(RunError::Runtime(msg)) = result else {
+            panic!("expected a runtime error");
+        };
+        assert!(
+            msg.starts_with("TypeError: Method 'f' is marked override but 'A' has no superclass."),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn test_runtime_errors_report_their_line() {
+        let source = "let a = 1;\nlet b = 2;\nprint c;".to_string();
+        let result = run(source, false, StoreMode::Vec, WarningMode::Print);
+        let Err(RunError::Runtime(msg)) = result else {
+            panic!("expected a runtime error");
+        };
+        assert!(msg.starts_with("NameError"), "{}", msg);
+        assert!(msg.ends_with("\n[line 3] in script"), "{}", msg);
+    }
+
+    #[test]
+    fn test_runtime_errors_report_the_call_stack() {
+        let source = "def inner() {\n  return 1 + true;\n}\ndef outer() {\n  return inner();\n}\nouter();";
+        let result = run(source.to_string(), true, StoreMode::Vec, WarningMode::Print);
+        let Err(RunError::Runtime(msg)) = result else {
+            panic!("expected a runtime error");
+        };
+        let trace = msg.lines().skip(1).collect::<Vec<&str>>();
+        assert_eq!(
+            trace,
+            vec!["[line 2] in inner()", "[line 5] in outer()", "[line 7] in script"]
+        );
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_warnings() {
+        let source = "let a = 1;\n{\n  let a = 2;\n  {\n    let a = 3;\n  }\n}".to_string();
+        assert!(run(source.clone(), true, StoreMode::Vec, WarningMode::Print).is_ok());
+        let result = run(source, true, StoreMode::Vec, WarningMode::Error);
+        assert!(matches!(result, Err(RunError::Warnings(warnings)) if warnings.len() == 1));
+    }
+
+    #[test]
+    fn test_echo_without_colors_has_no_escape_codes() {
+        let mut interpreter = Interpreter::new();
+        for source in ["42;", "\"text\";", "true;", "1.5;"] {
+            let value = interpreter.eval(source).unwrap();
+            let echoed = format_echo(&value, &interpreter, None);
+            assert!(!echoed.contains('\x1b'), "{:?}", echoed);
         }
+
+        let value = interpreter.eval("42;").unwrap();
+        let colors = ColorScheme::default();
+        let echoed = format_echo(&value, &interpreter, Some(&colors));
+        assert_eq!(echoed, format!("{}42\x1b[0m", colors.number));
+    }
+
+    #[test]
+    fn test_read_source_continues_incomplete_input() {
+        let mut reader = Cursor::new("def add(a, b) {\n  return a + b; }\nprint add(1, 2);\n");
+        let mut output = vec![];
+        let source = read_source(&mut reader, &mut output).unwrap();
+        assert_eq!(source.as_deref(), Some("def add(a, b) {\n  return a + b; }\n"));
+        assert_eq!(output, b">>> ... ");
+
+        let source = read_source(&mut reader, &mut output).unwrap();
+        assert_eq!(source.as_deref(), Some("print add(1, 2);\n"));
+        assert_eq!(read_source(&mut reader, &mut output).unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_source_stops_at_blank_line() {
+        let mut reader = Cursor::new("print 1 +\n\nprint 2;\n");
+        let mut output = vec![];
+        let source = read_source(&mut reader, &mut output).unwrap();
+        assert_eq!(source.as_deref(), Some("print 1 +\n\n"));
+        let source = read_source(&mut reader, &mut output).unwrap();
+        assert_eq!(source.as_deref(), Some("print 2;\n"));
     }
 }
@@ -1,48 +1,110 @@
 use std::collections::HashMap;
+use std::io::{self, Write};
+use std::rc::Rc;
 
 use log::info;
 
 use crate::builtins::bool::SoxBool;
-use crate::builtins::exceptions::{Exception, RuntimeError};
+use crate::builtins::builtin_function::SoxBuiltinFunction;
+use crate::builtins::exceptions::{ErrorKind, Exception, RuntimeError};
 use crate::builtins::float::SoxFloat;
 use crate::builtins::function::SoxFunction;
 use crate::builtins::int::SoxInt;
+use crate::builtins::list::SoxList;
 use crate::builtins::method::FuncArgs;
+use crate::builtins::natives::NATIVES;
 use crate::builtins::none::SoxNone;
 use crate::builtins::r#type::{SoxInstance, SoxType};
 use crate::builtins::string::SoxString;
 use crate::catalog::TypeLibrary;
 use crate::core::SoxObjectPayload;
 use crate::core::SoxRef;
-use crate::core::{SoxObject, SoxResult};
-use crate::environment::{EnvRef, Environment};
-use crate::expr::Expr;
+use crate::core::{Representable, SoxObject, SoxResult};
+use crate::environment::{EnvRef, Environment, StoreMode};
+use crate::init;
+use crate::init::WarningMode;
+use crate::expr::{Expr, VarRef};
 use crate::expr::ExprVisitor;
 use crate::stmt::{Stmt, StmtVisitor};
 use crate::token::{Literal, Token};
 use crate::token_type::TokenType;
 
+/// The result of dividing two ints with `/`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IntDivision {
+    /// A float, as in Python: `7 / 2` is `3.5`.
+    #[default]
+    Float,
+    /// An int truncated toward zero, as in C: `7 / 2` is `3` and `-7 / 2` is `-3`.
+    Truncate,
+}
+
+/// The default for `Interpreter::max_call_depth`.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
 pub struct Interpreter {
     pub environment: Environment,
     pub types: TypeLibrary,
     pub none: SoxRef<SoxNone>,
-    pub locals: HashMap<Token, (usize, usize)>,
+    pub locals: HashMap<VarRef, (usize, usize)>,
+    pub warning_mode: WarningMode,
+    /// Show the fields of instances whose class has no `str` method in their repr, e.g.
+    /// `Point { x: 1, y: 2 }` instead of `<Point instance>`.
+    pub debug_repr: bool,
+    pub int_division: IntDivision,
+    /// Where `print` and `write()` send the program's output; stdout unless an embedding
+    /// host redirects it.
+    pub output: Box<dyn Write>,
+    /// How many sox functions may be executing at once before a call raises a
+    /// `RecursionError`.
+    pub max_call_depth: usize,
+    pub(crate) call_depth: usize,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        
+        Interpreter::with_store_mode(StoreMode::default())
+    }
+
+    pub fn with_store_mode(store_mode: StoreMode) -> Self {
         let types = TypeLibrary::init();
         let none = SoxRef::new(SoxNone {});
-        let interpreter = Interpreter {
-            environment: Environment::new(),
+        let mut interpreter = Interpreter {
+            environment: Environment::with_store_mode(store_mode),
             types,
             none,
             locals: Default::default(),
+            warning_mode: WarningMode::default(),
+            debug_repr: false,
+            int_division: IntDivision::default(),
+            output: Box::new(io::stdout()),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            call_depth: 0,
         };
+        interpreter.install_builtins();
         interpreter
     }
 
+    /// Defines the native functions and the callable builtin type objects in the global
+    /// namespace.
+    pub fn install_builtins(&mut self) {
+        for (name, method) in NATIVES {
+            let native = SoxBuiltinFunction::new(name.to_string(), method.clone());
+            self.environment.define(name, native.into_ref());
+        }
+        for typ in [
+            self.types.int_type,
+            self.types.float_type,
+            self.types.str_type,
+            self.types.list_type,
+        ] {
+            if let Some(type_object) = self.types.type_object(typ) {
+                let name = type_object.name.clone().unwrap_or_default();
+                self.environment.define(name, type_object.to_sox_object());
+            }
+        }
+    }
+
     pub fn new_string(&self, s: String) -> SoxObject {
         let str = SoxRef::new(SoxString::from(s));
         str.to_sox_object()
@@ -64,26 +126,59 @@ impl Interpreter {
         SoxNone {}.into_ref()
     }
 
+    /// Executes `statements` and prints the value of the last one, unless it is None, or
+    /// the error that stopped execution.
     pub fn interpret(&mut self, statements: &Vec<Stmt>) {
-        let mut m = statements.iter().peekable();
-        while let Some(stmt) = m.next() {
+        let text = match self.execute_program(statements) {
+            Ok(SoxObject::None(_)) => return,
+            Ok(value) => value.repr(self),
+            Err(error) => {
+                // whatever the program printed before failing comes first
+                let _ = self.output.flush();
+                self.error_report(&error)
+            }
+        };
+        let _ = writeln!(self.output, "{}", text);
+        let _ = self.output.flush();
+    }
+
+    /// Lexes, parses, resolves and executes `source` against this interpreter's state and
+    /// returns the value of the last statement, or the error that stopped execution. Syntax
+    /// and resolution errors are returned as errors too. Nothing is printed apart from what
+    /// the program itself prints.
+    pub fn eval(&mut self, source: &str) -> SoxResult {
+        let ast = init::prepare(self, source, true, self.warning_mode)
+            .map_err(|e| Interpreter::runtime_error(e.to_string()))?;
+        self.execute_program(&ast)
+    }
+
+    /// Executes `statements` and returns the result of each top-level statement, for hosts
+    /// that display every result. Execution stops at the first error, which is the last
+    /// result. `init::prepare` turns source into statements ready to run here.
+    pub fn run_collecting(&mut self, statements: &[Stmt]) -> Vec<SoxResult> {
+        let mut results = Vec::with_capacity(statements.len());
+        for stmt in statements {
             let result = self.execute(stmt);
-            if result.is_err() {
-                println!("{}", result.unwrap_err().repr(&self));
+            let failed = result.is_err();
+            results.push(result);
+            if failed {
                 break;
             }
-            let result_value = result.unwrap();
-            if m.peek().is_none() {
-                if let SoxObject::None(_) = result_value {
-                } else {
-                    println!("{}", result_value.repr(&self));
-                }
-            }
         }
+        results
+    }
+
+    // the value of a program is the result of its last statement, or None if it's empty
+    pub(crate) fn execute_program(&mut self, statements: &[Stmt]) -> SoxResult {
+        let mut result_value = self.new_none();
+        for stmt in statements {
+            result_value = self.execute(stmt)?;
+        }
+        Ok(result_value)
     }
 
-    fn evaluate(&mut self, expr: &Expr) -> SoxResult {
-        expr.accept(self)
+    pub(crate) fn evaluate(&mut self, expr: &Expr) -> SoxResult {
+        expr.accept(&mut *self).map_err(|error| Interpreter::locate(error, expr))
     }
 
     fn execute(&mut self, stmt: &Stmt) -> SoxResult {
@@ -112,8 +207,54 @@ impl Interpreter {
         Ok(())
     }
 
-    fn lookup_variable(&mut self, name: &Token) -> SoxResult {
-        if let Some(dist) = self.locals.get(name) {
+    // runs one iteration of a loop body; a `continue` ends the iteration but not the loop
+    fn execute_loop_body(&mut self, body: &Stmt) -> SoxResult<()> {
+        match self.execute(body) {
+            Err(err) if matches!(err.as_exception().as_deref(), Some(Exception::Continue)) => {
+                Ok(())
+            }
+            result => result.map(|_| ()),
+        }
+    }
+
+    // runs a for loop in the already active namespace of its initializer
+    fn run_for_loop(
+        &mut self,
+        initializer: Option<&Stmt>,
+        condition: Option<&Expr>,
+        increment: Option<&Expr>,
+        body: &Stmt,
+    ) -> SoxResult<()> {
+        if let Some(initializer) = initializer {
+            self.execute(initializer)?;
+        }
+        loop {
+            if let Some(condition) = condition {
+                let cond = self.evaluate(condition)?;
+                if !cond.try_into_rust_bool(self) {
+                    return Ok(());
+                }
+            }
+            self.execute_loop_body(body)?;
+            if let Some(increment) = increment {
+                self.evaluate(increment)?;
+            }
+        }
+    }
+
+    // runs the statements of a block expression in the already active block namespace
+    fn evaluate_block(&mut self, statements: &[Stmt], value: Option<&Expr>) -> SoxResult {
+        for statement in statements {
+            self.execute(statement)?;
+        }
+        match value {
+            Some(value) => self.evaluate(value),
+            None => Ok(self.none.into_ref()),
+        }
+    }
+
+    fn lookup_variable(&mut self, name: &Token, var: VarRef) -> SoxResult {
+        if let Some(dist) = self.locals.get(&var) {
             let (dst, binding_idx) = dist;
             let key = (name.lexeme.to_string(), *dst, *binding_idx);
             let val = self.environment.get(key);
@@ -126,10 +267,282 @@ impl Interpreter {
         }
     }
 
+    /// Calls the method `name` that the class of `instance` defines, or returns `None` if
+    /// `instance` isn't a class instance or its class has no such method.
+    fn call_class_method(
+        &mut self,
+        instance: &SoxObject,
+        name: &str,
+        args: Vec<SoxObject>,
+    ) -> Option<SoxResult> {
+        let SoxObject::TypeInstance(inst) = instance else {
+            return None;
+        };
+        let method = inst.class_ref().find_method(name)?;
+        let func = method.as_func()?;
+        Some(
+            func.bind(instance.clone(), self)
+                .and_then(|bound_method| SoxFunction::call(bound_method, FuncArgs::new(args), self)),
+        )
+    }
+
+    /// Compares two values with `==`. Builtin types use their `equals` method; instances use
+    /// the `equals` method of their class if it defines one and are otherwise only equal to
+    /// themselves.
+    pub(crate) fn values_equal(&mut self, lhs: &SoxObject, rhs: &SoxObject) -> SoxResult<bool> {
+        if let SoxObject::TypeInstance(inst) = lhs {
+            return match self.call_class_method(lhs, "equals", vec![rhs.clone()]) {
+                Some(result) => Ok(result?.try_into_rust_bool(self)),
+                None => Ok(matches!(
+                    rhs,
+                    SoxObject::TypeInstance(other) if Rc::ptr_eq(&inst.val, &other.val)
+                )),
+            };
+        }
+        // primitives compare directly rather than through their `equals` method, which
+        // would clone both operands into a `FuncArgs` on every comparison
+        match (lhs, rhs) {
+            (SoxObject::Int(a), SoxObject::Int(b)) => return Ok(a.value == b.value),
+            (SoxObject::Float(a), SoxObject::Float(b)) => return Ok(a.value == b.value),
+            (SoxObject::Boolean(a), SoxObject::Boolean(b)) => return Ok(a.value == b.value),
+            (SoxObject::String(a), SoxObject::String(b)) => return Ok(a.value == b.value),
+            (SoxObject::None(_), SoxObject::None(_)) => return Ok(true),
+            _ => {}
+        }
+        let eq = lhs.sox_type(self).slots.methods.iter().find(|v| v.0 == "equals");
+        match eq {
+            Some(entry) => {
+                let call_args = FuncArgs::new(vec![lhs.clone(), rhs.clone()]);
+                let result = (entry.1.func)(self, call_args)?;
+                Ok(result.try_into_rust_bool(self))
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// `%` is floored, as in Python: a non-zero result takes the sign of the divisor, so
+    /// `-7 % 3` is `2` and `7 % -3` is `-2`.
+    fn int_modulo(lhs: i64, rhs: i64) -> i64 {
+        let rem = lhs.wrapping_rem(rhs);
+        if rem != 0 && (rem < 0) != (rhs < 0) {
+            rem + rhs
+        } else {
+            rem
+        }
+    }
+
+    /// The floored modulo of two floats; see `int_modulo`.
+    fn float_modulo(lhs: f64, rhs: f64) -> f64 {
+        let rem = lhs % rhs;
+        if rem != 0.0 && (rem < 0.0) != (rhs < 0.0) {
+            rem + rhs
+        } else {
+            rem
+        }
+    }
+
+    fn bitwise_op(operator: TokenType, lhs: i64, rhs: i64) -> SoxResult<i64> {
+        let shift = |shift_fn: fn(i64, u32) -> Option<i64>| {
+            if rhs < 0 {
+                return Err(Interpreter::typed_error(
+                    ErrorKind::ValueError,
+                    "negative shift count".into(),
+                ));
+            }
+            u32::try_from(rhs)
+                .ok()
+                .and_then(|rhs| shift_fn(lhs, rhs))
+                .ok_or_else(|| {
+                    Interpreter::typed_error(
+                        ErrorKind::ValueError,
+                        "shift count out of range".into(),
+                    )
+                })
+        };
+        match operator {
+            TokenType::Ampersand => Ok(lhs & rhs),
+            TokenType::Pipe => Ok(lhs | rhs),
+            TokenType::Caret => Ok(lhs ^ rhs),
+            TokenType::LessLess => shift(i64::checked_shl),
+            TokenType::GreaterGreater => shift(i64::checked_shr),
+            _ => Err(Interpreter::runtime_error(format!(
+                "{:?} is not a bitwise operator",
+                operator
+            ))),
+        }
+    }
+
+    /// Converts a value to the text `print` and `str()` show: the result of the instance's
+    /// `str` method when its class defines one, otherwise the value's repr.
+    pub fn stringify(&mut self, value: &SoxObject) -> SoxResult<String> {
+        let Some(text) = self.call_class_method(value, "str", vec![]) else {
+            return Ok(value.repr(self));
+        };
+        let text = text?;
+        match text.as_string() {
+            Some(text) => Ok(text.value.clone()),
+            None => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "str() returned a non-string value of type '{}'",
+                    text.sox_type(self).name.clone().unwrap_or_default()
+                ),
+            )),
+        }
+    }
+
+    /// Calls `callee` with `args`, the way a call expression does.
+    pub fn call(&mut self, callee: SoxObject, args: FuncArgs) -> SoxResult {
+        match callee.sox_type(self).slots.call {
+            Some(call) => call(callee, args, self),
+            None => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "{} object is not callable.",
+                    callee.sox_type(self).name.clone().unwrap_or_default()
+                ),
+            )),
+        }
+    }
+
+    /// The value of an index into a builtin list or string, which has to be an int.
+    fn sequence_index(&self, sequence: &SoxObject, index: &SoxObject) -> SoxResult<i64> {
+        index.as_int().map(|index| index.value).ok_or_else(|| {
+            Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "{} indices must be integers, not '{}'",
+                    sequence.sox_type(self).name.clone().unwrap_or_default(),
+                    index.sox_type(self).name.clone().unwrap_or_default()
+                ),
+            )
+        })
+    }
+
+    /// Evaluates a bound of a slice; a missing bound and `None` both leave it open.
+    fn slice_bound(&mut self, bound: Option<&Expr>) -> SoxResult<Option<i64>> {
+        let Some(bound) = bound else {
+            return Ok(None);
+        };
+        match self.evaluate(bound)? {
+            SoxObject::Int(value) => Ok(Some(value.value)),
+            SoxObject::None(_) => Ok(None),
+            _ => Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                "slice indices must be integers or None".into(),
+            )),
+        }
+    }
+
+    fn unsupported_comparison(
+        &self,
+        operator: &Token,
+        lhs: &SoxObject,
+        rhs: &SoxObject,
+    ) -> SoxObject {
+        Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!(
+                "'{}' not supported between '{}' and '{}'",
+                operator.lexeme,
+                lhs.sox_type(self).name.clone().unwrap_or_default(),
+                rhs.sox_type(self).name.clone().unwrap_or_default()
+            ),
+        )
+    }
+
+    fn not_subscriptable(&self, object: &SoxObject) -> SoxObject {
+        Interpreter::typed_error(
+            ErrorKind::TypeError,
+            format!(
+                "'{}' object is not subscriptable",
+                object.sox_type(self).name.clone().unwrap_or_default()
+            ),
+        )
+    }
+
     pub fn runtime_error(msg: String) -> SoxObject {
-        let error = Exception::Err(RuntimeError { msg });
+        Interpreter::typed_error(ErrorKind::RuntimeError, msg)
+    }
+
+    pub fn typed_error(kind: ErrorKind, msg: String) -> SoxObject {
+        let error = Exception::Err(RuntimeError::new(kind, msg));
         error.into_ref()
     }
+
+    /// `value is typ`: whether `value` is the type object `typ` itself, or an object of the
+    /// class `typ` or of a class derived from it. Type objects are compared by identity;
+    /// builtin types have a single object each, so this holds for them as well.
+    pub(crate) fn is_a(&self, value: &SoxObject, typ: &SoxObject) -> SoxResult<bool> {
+        let Some(typ) = typ.as_type() else {
+            return Err(Interpreter::typed_error(
+                ErrorKind::TypeError,
+                format!(
+                    "the right operand of 'is' must be a type, not '{}'",
+                    typ.sox_type(self).name.clone().unwrap_or_default()
+                ),
+            ));
+        };
+        let mut class = match value {
+            SoxObject::Type(value) => return Ok(Rc::ptr_eq(&value.val, &typ.val)),
+            SoxObject::TypeInstance(instance) => Some(instance.class_ref()),
+            _ => self.types.type_object(value.sox_type(self)),
+        };
+        while let Some(current) = class {
+            if Rc::ptr_eq(&current.val, &typ.val) {
+                return Ok(true);
+            }
+            class = current.base.clone();
+        }
+        Ok(false)
+    }
+
+    /// The value a `catch` clause binds for `error`: the report of an interpreter error as a
+    /// string, or the value thrown. Only errors are caught, so this is `None` for returns
+    /// and the like, which have to keep unwinding.
+    pub(crate) fn caught_value(&self, error: &SoxObject) -> Option<SoxObject> {
+        match error.as_exception().as_deref() {
+            Some(Exception::Err(e)) => Some(self.new_string(e.repr(self))),
+            Some(Exception::Thrown(value)) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    /// Applies `f` to `error` if it is an interpreter error; other exceptions are returned
+    /// unchanged.
+    fn map_runtime_error(
+        error: SoxObject,
+        f: impl FnOnce(RuntimeError) -> RuntimeError,
+    ) -> SoxObject {
+        match error.as_exception().as_deref() {
+            Some(Exception::Err(e)) => Exception::Err(f(e.clone())).into_ref(),
+            _ => error,
+        }
+    }
+
+    /// Records that `error` was raised while evaluating `expr`, unless it already knows
+    /// where it came from.
+    fn locate(error: SoxObject, expr: &Expr) -> SoxObject {
+        match expr.line() {
+            Some(line) => Interpreter::map_runtime_error(error, |e| e.at_line(line)),
+            None => error,
+        }
+    }
+
+    fn error_line(error: &SoxObject) -> Option<usize> {
+        match error.as_exception().as_deref() {
+            Some(Exception::Err(e)) => e.line,
+            _ => None,
+        }
+    }
+
+    /// Renders an uncaught error along with where it was raised.
+    pub fn error_report(&self, error: &SoxObject) -> String {
+        match error.as_exception() {
+            Some(exception) => exception.report(self),
+            None => error.repr(self),
+        }
+    }
 }
 
 impl StmtVisitor for &mut Interpreter {
@@ -148,15 +561,19 @@ impl StmtVisitor for &mut Interpreter {
     }
 
     fn visit_print_stmt(&mut self, stmt: &Stmt) -> Self::T {
-        let return_value = if let Stmt::Print(expr) = stmt {
-            let value = self.evaluate(expr);
-            match value {
-                Ok(v) => {
-                    println!("{}", v.repr(&self));
-                    Ok(self.none.into_ref())
-                }
-                Err(v) => Err(v.into()),
+        let return_value = if let Stmt::Print(exprs) = stmt {
+            let mut texts = vec![];
+            for expr in exprs {
+                let value = self.evaluate(expr)?;
+                let text = self
+                    .stringify(&value)
+                    .map_err(|error| Interpreter::locate(error, expr))?;
+                texts.push(text);
             }
+            writeln!(self.output, "{}", texts.join(" ")).map_err(|e| {
+                Interpreter::runtime_error(format!("Failed to write output - {}", e))
+            })?;
+            Ok(self.none.into_ref())
         } else {
             Err(Interpreter::runtime_error(
                 "Evaluation failed - visited non print statement with visit_print_stmt."
@@ -189,6 +606,19 @@ impl StmtVisitor for &mut Interpreter {
         Ok(self.none.into_ref())
     }
 
+    fn visit_const_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Const { name, initializer } = stmt {
+            let value = self.evaluate(initializer)?;
+            self.environment.define(name.lexeme.to_string(), value);
+            Ok(self.none.into_ref())
+        } else {
+            Err(Interpreter::runtime_error(
+                "Evaluation failed - visiting a non const statement with visit_const_stmt."
+                    .to_string(),
+            ))
+        }
+    }
+
     fn visit_block_stmt(&mut self, stmt: &Stmt) -> Self::T {
         if let Stmt::Block(statements) = stmt {
             let stmts = statements.iter().map(|v| v).collect::<Vec<&Stmt>>();
@@ -229,7 +659,7 @@ impl StmtVisitor for &mut Interpreter {
         if let Stmt::While { condition, body } = stmt {
             let mut cond = self.evaluate(condition)?;
             while cond.try_into_rust_bool(self) {
-                self.execute(body)?;
+                self.execute_loop_body(body)?;
                 cond = self.evaluate(&condition)?;
             }
 
@@ -242,12 +672,38 @@ impl StmtVisitor for &mut Interpreter {
         }
     }
 
-    fn visit_function_stmt(&mut self, stmt: &Stmt) -> Self::T {
-        if let Stmt::Function {
-            name,
-            params,
-            body: _body,
+    fn visit_for_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::For {
+            initializer,
+            condition,
+            increment,
+            body,
         } = stmt
+        {
+            self.environment.new_local_env();
+            let result = self.run_for_loop(
+                initializer.as_deref(),
+                condition.as_ref(),
+                increment.as_ref(),
+                body,
+            );
+            let popped = self.environment.pop();
+            result?;
+            popped?;
+            Ok(self.none.into_ref())
+        } else {
+            Err(Interpreter::runtime_error(
+                "Evaluation failed - visited non for statement with visit_for_stmt.".to_string(),
+            ))
+        }
+    }
+
+    fn visit_continue_stmt(&mut self, _stmt: &Stmt) -> Self::T {
+        Err(Exception::Continue.into_ref())
+    }
+
+    fn visit_function_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Function { name, params, .. } = stmt
         {
             let stmt_clone = stmt.clone();
             let fo = SoxFunction::new(
@@ -269,12 +725,12 @@ impl StmtVisitor for &mut Interpreter {
     }
 
     fn visit_return_stmt(&mut self, stmt: &Stmt) -> Self::T {
-        let mut return_value = self.none.into_ref();
-        if let Stmt::Return { keyword: _, value } = stmt {
-            if let Some(value) = value {
-                return_value = self.evaluate(value)?;
-            }
-        }
+        let return_value = match stmt {
+            Stmt::Return {
+                value: Some(value), ..
+            } => self.evaluate(value)?,
+            _ => self.none.into_ref(),
+        };
         Err(Exception::Return(return_value).into_ref())
     }
 
@@ -283,6 +739,8 @@ impl StmtVisitor for &mut Interpreter {
             name,
             superclass,
             methods,
+            overrides,
+            getters,
         } = stmt
         {
             // get super class if exist
@@ -293,12 +751,39 @@ impl StmtVisitor for &mut Interpreter {
                     info!("Evaluated to a class");
                     Some(v)
                 } else {
-                    let re = Interpreter::runtime_error("Superclass must be a class.".to_string());
+                    let re = Interpreter::typed_error(
+                        ErrorKind::TypeError,
+                        "Superclass must be a class.".to_string(),
+                    );
                     return Err(re);
                 }
             } else {
                 None
             };
+            for method in overrides {
+                // the resolver rejects this already, but it can be skipped
+                let Some(base) = sc.as_ref() else {
+                    let error = RuntimeError::new(
+                        ErrorKind::TypeError,
+                        format!(
+                            "Method '{}' is marked override but '{}' has no superclass.",
+                            method.lexeme, name.lexeme
+                        ),
+                    );
+                    return Err(Exception::Err(error.at_line(method.line)).into_ref());
+                };
+                if !base.defines_method(&method.lexeme) {
+                    let error = RuntimeError::new(
+                        ErrorKind::TypeError,
+                        format!(
+                            "Method '{}' is marked override but '{}' has no such method.",
+                            method.lexeme,
+                            base.name.clone().unwrap_or_default()
+                        ),
+                    );
+                    return Err(Exception::Err(error.at_line(method.line)).into_ref());
+                }
+            }
             let none_val = self.none.clone().into_ref();
             // let active_env = self.active_env_mut();
             self.environment.define(name.lexeme.to_string(), none_val);
@@ -316,8 +801,8 @@ impl StmtVisitor for &mut Interpreter {
             for method in methods.iter() {
                 if let Stmt::Function {
                     name,
-                    body: _body,
                     params: _params,
+                    ..
                 } = method
                 {
                     let func = SoxFunction {
@@ -326,6 +811,8 @@ impl StmtVisitor for &mut Interpreter {
                         environment_ref: self.environment.active.clone(),
                         is_initializer: name.lexeme == "init".to_string(),
                         arity: _params.len() as i8,
+                        bound_class: None,
+                        is_getter: getters.iter().any(|getter| getter.lexeme == name.lexeme),
                     };
                     methods_map.insert(name.lexeme.clone().into(), func.into_ref());
                 }
@@ -355,15 +842,81 @@ impl StmtVisitor for &mut Interpreter {
         };
         ret_val
     }
+
+    fn visit_throw_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Throw { value, .. } = stmt {
+            let value = self.evaluate(value)?;
+            Err(Exception::Thrown(value).into_ref())
+        } else {
+            Err(Interpreter::runtime_error(
+                "Evaluation failed - visited non throw statement with visit_throw_stmt.".into(),
+            ))
+        }
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Try {
+            body,
+            catch_name,
+            catch_body,
+        } = stmt
+        {
+            let result = self.execute_block(body.iter().collect(), None);
+            if let Err(err) = result {
+                let Some(caught) = self.caught_value(&err) else {
+                    return Err(err);
+                };
+                let catch_env = self.environment.new_local_env_unused();
+                self.environment.define_at(
+                    catch_name.lexeme.to_string(),
+                    caught,
+                    catch_env.clone(),
+                );
+                self.execute_block(catch_body.iter().collect(), Some(catch_env))?;
+            }
+            Ok(self.none.into_ref())
+        } else {
+            Err(Interpreter::runtime_error(
+                "Evaluation failed - visited non try statement with visit_try_stmt.".into(),
+            ))
+        }
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Match {
+            subject,
+            cases,
+            default,
+        } = stmt
+        {
+            let subject = self.evaluate(subject)?;
+            let mut body = default.as_ref();
+            for (value, case_body) in cases {
+                let value = self.evaluate(value)?;
+                if self.values_equal(&subject, &value)? {
+                    body = Some(case_body);
+                    break;
+                }
+            }
+            if let Some(body) = body {
+                self.execute_block(body.iter().collect(), None)?;
+            }
+            Ok(self.none.into_ref())
+        } else {
+            Err(Interpreter::runtime_error(
+                "Evaluation failed - visited non match statement with visit_match_stmt.".into(),
+            ))
+        }
+    }
 }
 
 impl ExprVisitor for &mut Interpreter {
     type T = Result<SoxObject, SoxObject>;
 
     fn visit_assign_expr(&mut self, expr: &Expr) -> Self::T {
-        let ret_val = if let Expr::Assign { name, value } = expr {
+        let ret_val = if let Expr::Assign { name, value, var } = expr {
             let eval_val = self.evaluate(value)?;
-            let dist = self.locals.get(&name);
+            let dist = self.locals.get(var);
             if dist.is_some() {
                 let (dst, idx) = dist.unwrap();
                 // info!("Distance found from resolution is {dst}");
@@ -404,6 +957,33 @@ impl ExprVisitor for &mut Interpreter {
         value
     }
 
+    fn visit_list_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::List { elements } = expr {
+            let mut values = vec![];
+            for element in elements {
+                values.push(self.evaluate(element)?);
+            }
+            Ok(SoxList::new(values).into_ref())
+        } else {
+            Err(Interpreter::runtime_error(
+                "Evaluation failed - called visit_list_expr on a non list expression".into(),
+            ))
+        }
+    }
+
+    fn visit_block_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::Block { statements, value } = expr {
+            self.environment.new_local_env();
+            let result = self.evaluate_block(statements, value.as_deref());
+            self.environment.pop()?;
+            result
+        } else {
+            Err(Interpreter::runtime_error(
+                "Evaluation failed - called visit_block_expr on non block expression".into(),
+            ))
+        }
+    }
+
     fn visit_binary_expr(&mut self, expr: &Expr) -> Self::T {
         let value = if let Expr::Binary {
             left,
@@ -414,9 +994,24 @@ impl ExprVisitor for &mut Interpreter {
             let right_val = self.evaluate(right)?;
             let left_val = self.evaluate(left)?;
 
+            // classes can implement arithmetic operators for their instances
+            let method = match operator.token_type {
+                TokenType::Plus => Some("add"),
+                TokenType::Minus => Some("sub"),
+                TokenType::Star => Some("mul"),
+                TokenType::Slash => Some("div"),
+                _ => None,
+            };
+            if let Some(result) = method
+                .and_then(|method| self.call_class_method(&left_val, method, vec![right_val.clone()]))
+            {
+                return result;
+            }
+
             match operator.token_type {
                 TokenType::Minus => {
-                    let exc = Err(Interpreter::runtime_error(
+                    let exc = Err(Interpreter::typed_error(
+                        ErrorKind::TypeError,
                         "Operands must be two numbers or two strings".into(),
                     ));
                     let value = if let (Some(v1), Some(v2)) =
@@ -443,24 +1038,44 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
                 TokenType::Rem => {
-                    let exc = Err(Interpreter::runtime_error(
+                    let exc = Err(Interpreter::typed_error(
+                        ErrorKind::TypeError,
                         "Arguments to the remainder operator must both be numbers".into(),
                     ));
                     let value = if let (Some(v1), Some(v2)) =
                         (left_val.as_int(), right_val.as_int())
                     {
-                        Ok(SoxInt::from(v1.value % v2.value).into_ref())
+                        if v2.value == 0 {
+                            return Err(Interpreter::typed_error(
+                                ErrorKind::ZeroDivisionError,
+                                "integer modulo by zero".into(),
+                            ));
+                        }
+                        Ok(SoxInt::from(Interpreter::int_modulo(v1.value, v2.value)).into_ref())
                     } else if left_val.as_float().is_some() || right_val.as_float().is_some() {
-                        if let (Some(v1), Some(v2)) = (left_val.as_float(), right_val.as_float()) {
-                            Ok(SoxFloat::from(v1.value % v2.value).into_ref())
+                        let operands = if let (Some(v1), Some(v2)) =
+                            (left_val.as_float(), right_val.as_float())
+                        {
+                            Some((v1.value, v2.value))
                         } else if let (Some(v1), Some(v2)) =
                             (left_val.as_float(), right_val.as_int())
                         {
-                            Ok(SoxFloat::from(v1.value % (v2.value as f64)).into_ref())
+                            Some((v1.value, v2.value as f64))
                         } else if let (Some(v1), Some(v2)) =
                             (left_val.as_int(), right_val.as_float())
                         {
-                            Ok(SoxFloat::from((v1.value as f64) % v2.value).into_ref())
+                            Some((v1.value as f64, v2.value))
+                        } else {
+                            None
+                        };
+                        if let Some((v1, v2)) = operands {
+                            if v2 == 0.0 {
+                                return Err(Interpreter::typed_error(
+                                    ErrorKind::ZeroDivisionError,
+                                    "float modulo by zero".into(),
+                                ));
+                            }
+                            Ok(SoxFloat::from(Interpreter::float_modulo(v1, v2)).into_ref())
                         } else {
                             exc
                         }
@@ -470,7 +1085,8 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
                 TokenType::Plus => {
-                    let exc = Err(Interpreter::runtime_error(
+                    let exc = Err(Interpreter::typed_error(
+                        ErrorKind::TypeError,
                         "Operands must be two numbers or two strings.".into(),
                     ));
                     let value = if let (Some(v1), Some(v2)) =
@@ -502,7 +1118,8 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
                 TokenType::Star => {
-                    let exc = Err(Interpreter::runtime_error(
+                    let exc = Err(Interpreter::typed_error(
+                        ErrorKind::TypeError,
                         "Arguments to the multiplication operator must both be numbers".into(),
                     ));
                     let value = if let (Some(v1), Some(v2)) =
@@ -529,13 +1146,27 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
                 TokenType::Slash => {
-                    let exc = Err(Interpreter::runtime_error(
+                    let exc = Err(Interpreter::typed_error(
+                        ErrorKind::TypeError,
                         "Arguments to the division operator must both be numbers".into(),
                     ));
                     let value = if let (Some(v1), Some(v2)) =
                         (left_val.as_int(), right_val.as_int())
                     {
-                        Ok(SoxFloat::from((v1.value as f64) / (v2.value as f64)).into_ref())
+                        match self.int_division {
+                            IntDivision::Float => {
+                                Ok(SoxFloat::from((v1.value as f64) / (v2.value as f64)).into_ref())
+                            }
+                            IntDivision::Truncate => {
+                                if v2.value == 0 {
+                                    return Err(Interpreter::typed_error(
+                                        ErrorKind::ZeroDivisionError,
+                                        "integer division by zero".into(),
+                                    ));
+                                }
+                                Ok(SoxInt::from(v1.value.wrapping_div(v2.value)).into_ref())
+                            }
+                        }
                     } else if left_val.as_float().is_some() || right_val.as_float().is_some() {
                         if let (Some(v1), Some(v2)) = (left_val.as_float(), right_val.as_float()) {
                             Ok(SoxFloat::from(v1.value / v2.value).into_ref())
@@ -556,9 +1187,7 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
                 TokenType::Less => {
-                    let exc = Err(Interpreter::runtime_error(
-                        "Arguments to the less than operator must both be numbers".into(),
-                    ));
+                    let exc = Err(self.unsupported_comparison(operator, &left_val, &right_val));
                     let value = if let (Some(v1), Some(v2)) =
                         (left_val.as_int(), right_val.as_int())
                     {
@@ -583,9 +1212,7 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
                 TokenType::Greater => {
-                    let exc = Err(Interpreter::runtime_error(
-                        "Arguments to the greater than operator must both be numbers".into(),
-                    ));
+                    let exc = Err(self.unsupported_comparison(operator, &left_val, &right_val));
                     let value = if let (Some(v1), Some(v2)) =
                         (left_val.as_int(), right_val.as_int())
                     {
@@ -611,31 +1238,19 @@ impl ExprVisitor for &mut Interpreter {
                 }
 
                 TokenType::EqualEqual => {
-                    let left_type = left_val.sox_type(self);
-                    let eq = left_type.slots.methods.iter().find(|v| v.0 == "equals");
-                    if let Some(entry) = eq {
-                        let call_args = FuncArgs::new(vec![left_val.clone(), right_val.clone()]);
-                        (entry.1.func)(self, call_args)
-                    } else {
-                        Ok(SoxBool::from(false).into_ref())
-                    }
-
+                    let equal = self.values_equal(&left_val, &right_val)?;
+                    Ok(SoxBool::from(equal).into_ref())
                 }
                 TokenType::BangEqual => {
-                    let left_type = left_val.sox_type(self);
-                    let eq = left_type.slots.methods.iter().find(|v| v.0 == "equals");
-                    let value = if let Some(entry) = eq {
-                        let call_args = FuncArgs::new(vec![left_val.clone(), right_val.clone()]);
-                        (entry.1.func)(self, call_args)
-                    } else {
-                        Ok(SoxBool::from(false).into_ref())
-                    };
-                    Ok(SoxBool::from(!value?.try_into_rust_bool(self)).into_ref())
+                    let equal = self.values_equal(&left_val, &right_val)?;
+                    Ok(SoxBool::from(!equal).into_ref())
+                }
+                TokenType::Is => {
+                    let is_a = self.is_a(&left_val, &right_val)?;
+                    Ok(SoxBool::from(is_a).into_ref())
                 }
                 TokenType::LessEqual => {
-                    let exc = Err(Interpreter::runtime_error(
-                        "Arguments to the less than or equals operator must both be numbers".into(),
-                    ));
+                    let exc = Err(self.unsupported_comparison(operator, &left_val, &right_val));
                     let value = if let (Some(v1), Some(v2)) =
                         (left_val.as_int(), right_val.as_int())
                     {
@@ -660,10 +1275,7 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
                 TokenType::GreaterEqual => {
-                    let exc = Err(Interpreter::runtime_error(
-                        "Arguments to the greater than or equals operator must both be numbers"
-                            .into(),
-                    ));
+                    let exc = Err(self.unsupported_comparison(operator, &left_val, &right_val));
                     let value = if let (Some(v1), Some(v2)) =
                         (left_val.as_int(), right_val.as_int())
                     {
@@ -687,9 +1299,22 @@ impl ExprVisitor for &mut Interpreter {
                     };
                     value
                 }
-                TokenType::Bang => {
-                    let value = right_val.try_into_rust_bool(self);
-                    Ok(SoxBool::from(value).into_ref())
+                TokenType::Ampersand
+                | TokenType::Pipe
+                | TokenType::Caret
+                | TokenType::LessLess
+                | TokenType::GreaterGreater => {
+                    let (Some(v1), Some(v2)) = (left_val.as_int(), right_val.as_int()) else {
+                        return Err(Interpreter::typed_error(
+                            ErrorKind::TypeError,
+                            format!(
+                                "Operands of the ({}) operator must both be integers",
+                                operator.lexeme
+                            ),
+                        ));
+                    };
+                    Interpreter::bitwise_op(operator.token_type, v1.value, v2.value)
+                        .map(|v| SoxInt::from(v).into_ref())
                 }
                 _ => Err(Interpreter::runtime_error("Unsupported token type".into())),
             }
@@ -724,7 +1349,8 @@ impl ExprVisitor for &mut Interpreter {
                         let new_val = SoxInt { value: -v.value };
                         Ok(new_val.into_ref())
                     } else {
-                        Err(Interpreter::runtime_error(
+                        Err(Interpreter::typed_error(
+                            ErrorKind::TypeError,
                             "The unary operator (-) can only be applied to a numeric value."
                                 .to_string(),
                         ))
@@ -732,7 +1358,7 @@ impl ExprVisitor for &mut Interpreter {
                     value
                 }
 
-                TokenType::Bang => {
+                TokenType::Bang | TokenType::Not => {
                     let value = right.try_into_rust_bool(self);
                     Ok(SoxBool::from(!value).into_ref())
                 }
@@ -755,14 +1381,13 @@ impl ExprVisitor for &mut Interpreter {
         } = expr
         {
             let left = self.evaluate(left)?;
-            if operator.token_type == TokenType::Or {
-                if left.clone().try_into_rust_bool(self) {
-                    return Ok(left);
-                }
-            } else {
-                if !left.clone().try_into_rust_bool(self) {
-                    return Ok(left);
-                }
+            let short_circuits = match operator.token_type {
+                TokenType::Or => left.clone().try_into_rust_bool(self),
+                TokenType::QuestionQuestion => left.as_none().is_none(),
+                _ => !left.clone().try_into_rust_bool(self),
+            };
+            if short_circuits {
+                return Ok(left);
             }
             self.evaluate(&right)
         } else {
@@ -774,8 +1399,8 @@ impl ExprVisitor for &mut Interpreter {
     }
 
     fn visit_variable_expr(&mut self, expr: &Expr) -> Self::T {
-        if let Expr::Variable { name } = expr {
-            self.lookup_variable(name)
+        if let Expr::Variable { name, var } = expr {
+            self.lookup_variable(name, *var)
         } else {
             Err(Interpreter::runtime_error(
                 "Evaluation failed - called visit_variable_expr on non variable expr.".into(),
@@ -786,29 +1411,32 @@ impl ExprVisitor for &mut Interpreter {
     fn visit_call_expr(&mut self, expr: &Expr) -> Self::T {
         if let Expr::Call {
             callee,
-            paren: _,
+            paren,
             arguments,
         } = expr
         {
             let callee_ = self.evaluate(callee)?;
+            let frame_name = match &callee_ {
+                SoxObject::Function(func) => Some(func.name.clone()),
+                SoxObject::BuiltinFunction(func) => Some(func.name.clone()),
+                SoxObject::Type(_) => Some("init".to_string()),
+                _ => None,
+            };
             let mut args = vec![];
             for argument in arguments {
                 let arg_val = self.evaluate(argument)?;
                 args.push(arg_val);
             }
-            let call_args = FuncArgs::new(args);
-            let callee_type = callee_.sox_type(self);
-            let callee_type_name = callee_type.name.clone().unwrap();
-            let ret_val = match callee_type.slots.call {
-                Some(fo) => {
-                    let val = (fo)(callee_, call_args, self);
-                    val
+            self.call(callee_, FuncArgs::new(args)).map_err(|error| {
+                // errors raised while running the callee's body already know their line;
+                // those raised by the call itself are located by `evaluate`
+                match frame_name {
+                    Some(name) if Interpreter::error_line(&error).is_some() => {
+                        Interpreter::map_runtime_error(error, |e| e.unwind(&name, paren.line))
+                    }
+                    _ => error,
                 }
-                _ => Err(Interpreter::runtime_error(
-                    format!("{} object is not callable.", callee_type_name),
-                )),
-            };
-            ret_val
+            })
         } else {
             Err(Interpreter::runtime_error(
                 "Can only call functions and classes".into(),
@@ -822,8 +1450,18 @@ impl ExprVisitor for &mut Interpreter {
                 //info!("Instance of type {:?}", inst.class(self));
 
                 SoxInstance::get(inst, name.clone(), self)
+            } else if let Some(method) = object
+                .as_type()
+                .and_then(|typ| typ.find_static_method(&name.lexeme))
+            {
+                let method = SoxBuiltinFunction::new(name.lexeme.clone(), method);
+                Ok(method.into_ref())
+            } else if let Some(method) = object.sox_type(self).methods.get(&name.lexeme).cloned() {
+                let method = SoxBuiltinFunction::bound(name.lexeme.clone(), method, object);
+                Ok(method.into_ref())
             } else {
-                Err(Interpreter::runtime_error(
+                Err(Interpreter::typed_error(
+                    ErrorKind::AttributeError,
                     "Only class instances have attributes".into(),
                 ))
             }
@@ -849,7 +1487,8 @@ impl ExprVisitor for &mut Interpreter {
                 v.set(name.clone(), value.clone());
                 Ok(value)
             } else {
-                Err(Interpreter::runtime_error(
+                Err(Interpreter::typed_error(
+                    ErrorKind::AttributeError,
                     "Only instances have fields".into(),
                 ))
             }
@@ -860,9 +1499,110 @@ impl ExprVisitor for &mut Interpreter {
         };
         ret_val
     }
+
+    fn visit_index_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::Index { object, index, .. } = expr {
+            let object = self.evaluate(object)?;
+            let index = self.evaluate(index)?;
+            match &object {
+                SoxObject::List(list) => {
+                    let index = self.sequence_index(&object, &index)?;
+                    list.get(index).ok_or_else(|| {
+                        Interpreter::typed_error(
+                            ErrorKind::IndexError,
+                            "list index out of range".into(),
+                        )
+                    })
+                }
+                SoxObject::String(string) => {
+                    let index = self.sequence_index(&object, &index)?;
+                    let character = string.get(index).ok_or_else(|| {
+                        Interpreter::typed_error(
+                            ErrorKind::IndexError,
+                            "string index out of range".into(),
+                        )
+                    })?;
+                    Ok(character.into_ref())
+                }
+                _ => match self.call_class_method(&object, "get_item", vec![index]) {
+                    Some(result) => result,
+                    None => Err(self.not_subscriptable(&object)),
+                },
+            }
+        } else {
+            Err(Interpreter::runtime_error(
+                "Calling visit_index_expr on none index expr".into(),
+            ))
+        }
+    }
+
+    fn visit_slice_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::Slice {
+            object, start, end, ..
+        } = expr
+        {
+            let object = self.evaluate(object)?;
+            let start = self.slice_bound(start.as_deref())?;
+            let end = self.slice_bound(end.as_deref())?;
+            match &object {
+                SoxObject::List(list) => Ok(list.slice(start, end).into_ref()),
+                SoxObject::String(string) => Ok(string.slice(start, end).into_ref()),
+                _ => Err(Interpreter::typed_error(
+                    ErrorKind::TypeError,
+                    format!(
+                        "'{}' object can't be sliced",
+                        object.sox_type(self).name.clone().unwrap_or_default()
+                    ),
+                )),
+            }
+        } else {
+            Err(Interpreter::runtime_error(
+                "Calling visit_slice_expr on none slice expr".into(),
+            ))
+        }
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::SetIndex {
+            object,
+            index,
+            value,
+            ..
+        } = expr
+        {
+            let object = self.evaluate(object)?;
+            let index = self.evaluate(index)?;
+            let value = self.evaluate(value)?;
+            match &object {
+                SoxObject::List(list) => {
+                    let index = self.sequence_index(&object, &index)?;
+                    if list.set(index, value.clone()) {
+                        Ok(value)
+                    } else {
+                        Err(Interpreter::typed_error(
+                            ErrorKind::IndexError,
+                            "list assignment index out of range".into(),
+                        ))
+                    }
+                }
+                SoxObject::String(_) => Err(Interpreter::typed_error(
+                    ErrorKind::TypeError,
+                    "'string' object does not support item assignment".into(),
+                )),
+                _ => match self.call_class_method(&object, "set_item", vec![index, value.clone()]) {
+                    Some(result) => result.map(|_| value),
+                    None => Err(self.not_subscriptable(&object)),
+                },
+            }
+        } else {
+            Err(Interpreter::runtime_error(
+                "Calling visit_set_index_expr on none set index expr".into(),
+            ))
+        }
+    }
     fn visit_this_expr(&mut self, expr: &Expr) -> Self::T {
-        if let Expr::This { keyword } = expr {
-            let value = self.lookup_variable(keyword);
+        if let Expr::This { keyword, var } = expr {
+            let value = self.lookup_variable(keyword, *var);
             value
         } else {
             Err(Interpreter::runtime_error(
@@ -871,13 +1611,16 @@ impl ExprVisitor for &mut Interpreter {
         }
     }
     fn visit_super_expr(&mut self, expr: &Expr) -> Self::T {
-        if let Expr::Super { keyword, method } = expr {
-            let (dist_to_ns, binding_idx) = self.locals.get(&keyword).unwrap();
-            let this_token = Token::new(TokenType::This, "this".to_string(), Literal::None, 0);
-            let (dist_to_ns2, binding_idx2) = self.locals.get(&this_token).unwrap();
-
-            let key = ("super".to_string(), *dist_to_ns, *binding_idx);
-            let key2 = ("this".to_string(), *dist_to_ns2, *binding_idx2);
+        if let Expr::Super { method, var, .. } = expr {
+            let Some((dist_to_ns, binding_idx)) = self.locals.get(var).cloned() else {
+                return Err(Interpreter::runtime_error(
+                    "Unable to resolve superclass - super".into(),
+                ));
+            };
+            // the namespace binding `this` is created when a method is bound and sits directly
+            // inside the namespace holding `super`, so it is always one level closer
+            let key = ("super".to_string(), dist_to_ns, binding_idx);
+            let key2 = ("this".to_string(), dist_to_ns - 1, 0);
 
             //let env = self.active_env_mut();
             let super_type = self.environment.get(key)?;
@@ -889,19 +1632,18 @@ impl ExprVisitor for &mut Interpreter {
                 let method = c.find_method(method_name.as_str());
                 let t = if let Some(m) = method {
                     if let Some(func) = m.as_func() {
-                        let bound_method = func.bind(instance, self)?;
-                        Ok(bound_method)
+                        func.property_value(instance, self)
                     } else {
-                        Err(Interpreter::runtime_error(format!(
-                            "Undefined property {}",
-                            method_name
-                        )))
+                        Err(Interpreter::typed_error(
+                            ErrorKind::AttributeError,
+                            format!("Undefined property {}", method_name),
+                        ))
                     }
                 } else {
-                    Err(Interpreter::runtime_error(format!(
-                        "Undefined property {}",
-                        method_name
-                    )))
+                    Err(Interpreter::typed_error(
+                        ErrorKind::AttributeError,
+                        format!("Undefined property {}", method_name),
+                    ))
                 };
                 t
             } else {
@@ -917,3 +1659,415 @@ impl ExprVisitor for &mut Interpreter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io;
+    use std::io::Write;
+    use std::rc::Rc;
+
+    use crate::core::SoxObject;
+    use crate::init;
+    use crate::init::WarningMode;
+    use crate::interpreter::{IntDivision, Interpreter};
+
+    /// An output sink that keeps everything written to it, shared with the test.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_eval_returns_value() {
+        let mut interpreter = Interpreter::new();
+        let value = interpreter.eval("1 + 2;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(3));
+    }
+
+    #[test]
+    fn test_eval_keeps_state_between_calls() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval("let a = 40;").unwrap();
+        let value = interpreter.eval("a + 2;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(42));
+    }
+
+    #[test]
+    fn test_eval_returns_errors() {
+        let mut interpreter = Interpreter::new();
+        let error = interpreter.eval("let a = ;").unwrap_err();
+        assert_eq!(
+            error.repr(&interpreter),
+            "[line 1] Error at ';': Expect an expression."
+        );
+        assert!(interpreter.eval("undefined;").is_err());
+    }
+
+    #[test]
+    fn test_failed_recursive_call_releases_namespaces() {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .eval(
+                r#"
+def countdown(n) {
+    if (n == 0) {
+        throw "bottom";
+    }
+    {
+        let depth = n;
+        countdown(n - 1);
+    }
+}
+class Walker {
+    walk(n) {
+        if (n == 0) {
+            throw "bottom";
+        }
+        this.walk(n - 1);
+    }
+}
+let walker = Walker();
+"#,
+            )
+            .unwrap();
+        let namespace_count = interpreter.environment.envs.len();
+
+        assert!(interpreter.eval("countdown(50);").is_err());
+        assert_eq!(interpreter.environment.envs.len(), namespace_count);
+        assert!(interpreter.eval("walker.walk(50);").is_err());
+        assert_eq!(interpreter.environment.envs.len(), namespace_count);
+        assert!(interpreter.eval("try { countdown(50); } catch (e) {}").is_ok());
+        assert_eq!(interpreter.environment.envs.len(), namespace_count);
+    }
+
+    #[test]
+    fn test_const_value_is_usable() {
+        let mut interpreter = Interpreter::new();
+        let value = interpreter
+            .eval("const base = 40;\ndef add(n) { const step = 2; return base + step * n; }\nadd(1);")
+            .unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(42));
+    }
+
+    #[test]
+    fn test_extreme_float_literals() {
+        let mut interpreter = Interpreter::new();
+        let value = interpreter.eval("1e400;").unwrap();
+        assert_eq!(value.as_float().map(|v| v.value), Some(f64::INFINITY));
+        let value = interpreter.eval("1e-400;").unwrap();
+        assert_eq!(value.as_float().map(|v| v.value), Some(0.0));
+    }
+
+    #[test]
+    fn test_bitwise_operators() {
+        let mut interpreter = Interpreter::new();
+        let value = interpreter.eval("6 & 3;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(2));
+        let value = interpreter.eval("1 << 4;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(16));
+        let value = interpreter.eval("6 | 1 ^ 3 & 5;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(6 | 1 ^ 3 & 5));
+
+        let error = interpreter.eval("1.5 & 1;").unwrap_err();
+        assert_eq!(
+            error.repr(&interpreter),
+            "TypeError: Operands of the (&) operator must both be integers"
+        );
+    }
+
+    #[test]
+    fn test_not_and_logical_operators() {
+        let mut interpreter = Interpreter::new();
+        let as_bool = |value: SoxObject| value.as_bool().map(|v| v.value);
+        assert_eq!(as_bool(interpreter.eval("!None;").unwrap()), Some(true));
+        assert_eq!(as_bool(interpreter.eval("!0;").unwrap()), Some(true));
+        assert_eq!(as_bool(interpreter.eval("!\"x\";").unwrap()), Some(false));
+
+        // the right operand isn't evaluated once the left one decides the result
+        assert_eq!(as_bool(interpreter.eval("true or (1 / 0);").unwrap()), Some(true));
+        assert_eq!(as_bool(interpreter.eval("false and (1 / 0);").unwrap()), Some(false));
+
+        // `and` binds tighter than `or`
+        assert_eq!(as_bool(interpreter.eval("true or false and false;").unwrap()), Some(true));
+        let value = interpreter.eval("None or 1 and 2;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(2));
+    }
+
+    #[test]
+    fn test_zero_and_empty_values_are_falsey() {
+        let mut interpreter = Interpreter::new();
+        let branch = |interpreter: &mut Interpreter, condition: &str| {
+            let source = format!("let taken = \"no\"; if ({}) taken = \"yes\"; taken;", condition);
+            let value = interpreter.eval(&source).unwrap();
+            value.as_string().map(|v| v.value.clone())
+        };
+        for condition in ["0", "\"\"", "0.0", "-0.0", "None", "false"] {
+            assert_eq!(branch(&mut interpreter, condition), Some("no".into()), "{}", condition);
+        }
+        for condition in ["1", "-1", "\" \"", "0.5", "true", "[]"] {
+            assert_eq!(branch(&mut interpreter, condition), Some("yes".into()), "{}", condition);
+        }
+    }
+
+    #[test]
+    fn test_block_expression_value() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval("let b = { let a = 1; a + 1 };").unwrap();
+        let value = interpreter.eval("b;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(2));
+        assert!(interpreter.eval("a;").is_err());
+    }
+
+    #[test]
+    fn test_static_method_on_builtin_type() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval("let int_type = type(1);").unwrap();
+        let value = interpreter.eval("int_type.from_str(\" 42 \");").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(42));
+        assert!(interpreter.eval("int_type.from_str(\"forty-two\");").is_err());
+        assert!(interpreter.eval("let one = 1; one.from_str(\"42\");").is_err());
+    }
+
+    #[test]
+    fn test_bare_and_valued_return() {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .eval("def bare() { return; } def five() { return 5; }")
+            .unwrap();
+        let value = interpreter.eval("bare();").unwrap();
+        assert!(matches!(value, SoxObject::None(_)));
+        let value = interpreter.eval("five();").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(5));
+    }
+
+    #[test]
+    fn test_chained_method_calls() {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .eval(
+                "class Counter {
+                    init() { this.count = 0; }
+                    add(n) { this.count = this.count + n; return this; }
+                    fresh() { return Counter().add(this.count); }
+                }",
+            )
+            .unwrap();
+        let value = interpreter.eval("Counter().add(1).add(2).add(3).count;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(6));
+
+        // each link binds the method to the object the previous call returned
+        interpreter.eval("let a = Counter().add(5); let b = a.fresh().add(1);").unwrap();
+        let value = interpreter.eval("a.count * 10 + b.count;").unwrap();
+        assert_eq!(value.as_int().map(|v| v.value), Some(56));
+    }
+
+    #[test]
+    fn test_builtins_are_installed_globally() {
+        let interpreter = Interpreter::new();
+        let names = interpreter.environment.global_names();
+        assert!(names.iter().any(|name| name == "str"));
+        assert!(names.iter().any(|name| name == "list"));
+    }
+
+    #[test]
+    fn test_modulo_is_floored() {
+        assert_eq!(Interpreter::int_modulo(-7, 3), 2);
+        assert_eq!(Interpreter::int_modulo(7, -3), -2);
+        assert_eq!(Interpreter::int_modulo(i64::MIN, -1), 0);
+        assert_eq!(Interpreter::float_modulo(5.5, 2.0), 1.5);
+        assert_eq!(Interpreter::float_modulo(-5.5, 2.0), 0.5);
+    }
+
+    #[test]
+    fn test_primitive_equality() {
+        let mut interpreter = Interpreter::new();
+        let source = r#"
+let matches = 0;
+for (let i = 0; i < 1000; i = i + 1) {
+    if (i % 10 == 3) matches = matches + 1;
+}
+matches;"#;
+        let matches = interpreter.eval(source).unwrap();
+        assert_eq!(matches.repr(&interpreter), "100");
+
+        let cases = [
+            ("1 == 1;", "true"),
+            ("1 == 1.0;", "false"),
+            ("0.5 == 0.5;", "true"),
+            ("\"a\" == \"a\";", "true"),
+            ("\"a\" != \"b\";", "true"),
+            ("true == true;", "true"),
+            ("None == None;", "true"),
+            ("None == false;", "false"),
+            ("1 == \"1\";", "false"),
+        ];
+        for (source, expected) in cases {
+            let result = interpreter.eval(source).unwrap();
+            assert_eq!(result.repr(&interpreter), expected, "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_int_division_is_configurable() {
+        let mut interpreter = Interpreter::new();
+        let quotient = interpreter.eval("7 / 2;").unwrap();
+        assert_eq!(quotient.repr(&interpreter), "3.5");
+
+        interpreter.int_division = IntDivision::Truncate;
+        let quotient = interpreter.eval("7 / 2;").unwrap();
+        assert_eq!(quotient.repr(&interpreter), "3");
+        let quotient = interpreter.eval("-7 / 2;").unwrap();
+        assert_eq!(quotient.repr(&interpreter), "-3");
+        let quotient = interpreter.eval("7.0 / 2;").unwrap();
+        assert_eq!(quotient.repr(&interpreter), "3.5");
+        assert!(interpreter.eval("7 / 0;").is_err());
+    }
+
+    #[test]
+    fn test_debug_repr_shows_fields() {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .eval("class Point { init(x, y) { this.x = x; this.y = y; } } let p = Point(1, \"two\");")
+            .unwrap();
+        let point = interpreter.eval("p;").unwrap();
+        assert_eq!(point.repr(&interpreter), "<Point instance>");
+
+        interpreter.debug_repr = true;
+        assert_eq!(point.repr(&interpreter), "Point { x: 1, y: \"two\" }");
+
+        interpreter.eval("p.x = p;").unwrap();
+        assert_eq!(point.repr(&interpreter), "Point { x: ..., y: \"two\" }");
+    }
+
+    #[test]
+    fn test_print_joins_its_values_with_spaces() {
+        let buffer = SharedBuffer::default();
+        let mut interpreter = Interpreter::new();
+        interpreter.output = Box::new(buffer.clone());
+        interpreter.eval("print; print \"one\"; print 1, \"two\", [3];").unwrap();
+
+        let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        assert_eq!(output, "\none\n1 two [3]\n");
+    }
+
+    #[test]
+    fn test_output_precedes_the_error_that_ends_a_program() {
+        let buffer = SharedBuffer::default();
+        let mut interpreter = Interpreter::new();
+        // buffered like a redirected stdout, so nothing reaches the sink until flushed
+        interpreter.output = Box::new(io::BufWriter::new(buffer.clone()));
+        let source = "print \"first\";\nprint \"second\";\nprint 1 / \"x\";";
+        let ast = init::prepare(&mut interpreter, source, true, WarningMode::Print).unwrap();
+        interpreter.interpret(&ast);
+
+        let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        assert_eq!(
+            output,
+            "first\nsecond\nTypeError: Arguments to the division operator must both be numbers\n[line 3] in script\n"
+        );
+    }
+
+    #[test]
+    fn test_run_collecting_returns_each_statement_result() {
+        let buffer = SharedBuffer::default();
+        let mut interpreter = Interpreter::new();
+        interpreter.output = Box::new(buffer.clone());
+        let source = "1 + 2;\n\"three\";\nprint 4;";
+        let ast = init::prepare(&mut interpreter, source, true, WarningMode::Print).unwrap();
+        let results = interpreter.run_collecting(&ast);
+
+        let results = results
+            .into_iter()
+            .map(|result| result.unwrap().repr(&interpreter))
+            .collect::<Vec<String>>();
+        assert_eq!(results, vec!["3", "three", "None"]);
+        let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        assert_eq!(output, "4\n");
+
+        // nothing runs after an error, which is the last result
+        let source = "1;\nprint 1 / \"x\";\n2;";
+        let ast = init::prepare(&mut interpreter, source, true, WarningMode::Print).unwrap();
+        let results = interpreter.run_collecting(&ast);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn test_recursion_past_the_limit_raises_a_catchable_error() {
+        let mut interpreter = Interpreter::new();
+        interpreter.max_call_depth = 50;
+        interpreter
+            .eval("def down(n) { if (n == 0) return 0; return down(n - 1) + 1; }")
+            .unwrap();
+        // down(n) is n + 1 calls deep
+        let value = interpreter.eval("down(49);").unwrap();
+        assert_eq!(value.repr(&interpreter), "49");
+
+        let error = interpreter.eval("down(50);").unwrap_err();
+        assert!(interpreter
+            .error_report(&error)
+            .starts_with("RecursionError: maximum recursion depth exceeded"));
+
+        let caught = interpreter
+            .eval("let caught = false; try { down(1000); } catch (e) { caught = true; } caught;")
+            .unwrap();
+        assert_eq!(caught.repr(&interpreter), "true");
+        // the depth unwinds with the error, so calls made afterwards aren't affected
+        assert_eq!(interpreter.call_depth, 0);
+        let value = interpreter.eval("down(49);").unwrap();
+        assert_eq!(value.repr(&interpreter), "49");
+    }
+
+    #[test]
+    fn test_default_recursion_limit_fits_a_test_thread_stack() {
+        let mut interpreter = Interpreter::new();
+        let source = "def f(n) { return f(n + 1) + 1; }\n\
+            let caught = None;\n\
+            try { f(0); } catch (e) { caught = e; }\n\
+            caught;";
+        let caught = interpreter.eval(source).unwrap();
+        assert_eq!(
+            caught.repr(&interpreter),
+            "RecursionError: maximum recursion depth exceeded"
+        );
+    }
+
+    #[test]
+    fn test_for_loop_matches_its_while_desugaring() {
+        let run = |source: &str| {
+            let buffer = SharedBuffer::default();
+            let mut interpreter = Interpreter::new();
+            interpreter.output = Box::new(buffer.clone());
+            interpreter.eval(source).unwrap();
+            let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+            output
+        };
+        let cases = [
+            (
+                "for (let i = 0; i < 3; i = i + 1) { print i; }",
+                "{ let i = 0; while (i < 3) { { print i; } i = i + 1; } }",
+            ),
+            (
+                "let fs = []; for (let i = 0; i < 3; i = i + 1) { let j = i; def f() { return i + j; } fs.append(f); } for (let k = 0; k < 3; k = k + 1) print fs[k]();",
+                "let fs = []; { let i = 0; while (i < 3) { { let j = i; def f() { return i + j; } fs.append(f); } i = i + 1; } } { let k = 0; while (k < 3) { print fs[k](); k = k + 1; } }",
+            ),
+            (
+                "let n = 0; for (; n < 2;) { print n; n = n + 1; }",
+                "let n = 0; while (n < 2) { print n; n = n + 1; }",
+            ),
+        ];
+        for (for_loop, desugared) in cases {
+            assert_eq!(run(for_loop), run(desugared), "{}", for_loop);
+        }
+    }
+}
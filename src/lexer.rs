@@ -2,21 +2,53 @@ use std::ops::Range;
 use log::debug;
 
 use crate::token::{Float, Literal, Token};
-use crate::token_type::TokenType;
+use crate::token_type::{TokenType, TO_IGNORE};
 use crate::token_type::TokenType::{
-    And, Bang, BangEqual, Class, Colon, Comma, Def, Dot, Else, Equal, EqualEqual, False, For,
-    Greater, GreaterEqual, Identifier, If, LeftBrace, LeftParen, Less, LessEqual, Let, Minus,
-    Newline, Number, Or, Plus, Print, Rem, Return, RightBrace, RightParen, Semi, Slash, SoxString,
-    Star, Super, This, True, While,
+    Ampersand, And, Bang, BangEqual, Caret, Case, Catch, Class, Colon, Comma, Const, Continue, Def,
+    Default, Dot, Else, Equal, EqualEqual, False, For, Greater, GreaterEqual, GreaterGreater,
+    Identifier, If, Is, LeftBrace, LeftParen, LeftSqb, Less, LessEqual, LessLess, Let, Match,
+    Minus, Newline, Not, Number, Or, Pipe, Plus, Print, QuestionQuestion, Rem, Return,
+    RightBrace, RightParen, RightSqb, Semi, Slash, SoxString, Star, Super, This, Throw, True, Try,
+    While,
 };
 
+#[derive(Debug, PartialEq)]
 pub struct LexError {
     msg: String,
+    line: usize,
 }
 
 impl LexError {
-    fn new(msg: String) -> Self {
-        LexError { msg }
+    fn new(msg: String, line: usize) -> Self {
+        LexError { msg, line }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    /// The line the malformed input is on.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+/// Lexes `source` into its significant tokens, leaving out whitespace, newlines and
+/// comments. Returns the errors of every token that couldn't be lexed instead, if any.
+pub fn tokenize(source: &str) -> Result<Vec<Token>, Vec<LexError>> {
+    let mut tokens = vec![];
+    let mut errors = vec![];
+    for token in Lexer::lex(source) {
+        if token.token_type == TokenType::Error {
+            errors.push(LexError::new(token.lexeme, token.line));
+        } else if !TO_IGNORE.contains(&token.token_type) {
+            tokens.push(token);
+        }
+    }
+    if errors.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(errors)
     }
 }
 
@@ -82,21 +114,31 @@ impl<'source> Lexer<'source> {
                 "false" => False,
                 "for" => For,
                 "if" => If,
+                "not" => Not,
                 "or" => Or,
                 "return" => Return,
                 "super" => Super,
                 "this" => This,
                 "true" => True,
                 "let" => Let,
+                "const" => Const,
                 "while" => While,
                 "def" => Def,
                 "print" => Print,
+                "throw" => Throw,
+                "try" => Try,
+                "catch" => Catch,
+                "continue" => Continue,
+                "match" => Match,
+                "case" => Case,
+                "default" => Default,
+                "is" => Is,
                 "None" => TokenType::None,
                 _ => Identifier,
             };
             Ok(self.yield_token(token_type.clone()))
         } else {
-            Err(LexError::new("".into()))
+            Err(LexError::new("".into(), self.line))
         }
     }
 
@@ -114,38 +156,124 @@ impl<'source> Lexer<'source> {
                     }
                 }
             }
+            if self.at_exponent() {
+                self.advance();
+                if let Some('+' | '-') = self.peek() {
+                    self.advance();
+                }
+                let exp_value = self.take_while(|ch| ch.is_ascii_digit());
+                if let Some((_, rng3)) = exp_value {
+                    end = rng3.end;
+                }
+            }
             let value: &str = &self.source[start..end];
-            if value.contains(".") {
-                let parsed_value = value.parse::<f64>().unwrap();
-                Ok(self.yield_literal_token(Number, Literal::Float(Float(parsed_value))))
+            if value.contains(['.', 'e', 'E']) {
+                // literals beyond the range of f64 become inf and ones too small to represent
+                // become 0.0, following IEEE 754 rounding
+                match value.parse::<f64>() {
+                    Ok(parsed_value) => {
+                        Ok(self.yield_literal_token(Number, Literal::Float(Float(parsed_value))))
+                    }
+                    Err(_) => Err(LexError::new(
+                        format!("Invalid float literal {}.", value),
+                        self.line,
+                    )),
+                }
             } else {
-                let parsed_value = value.parse::<i64>().unwrap();
-                Ok(self.yield_literal_token(Number, Literal::Integer(parsed_value)))
+                match value.parse::<i64>() {
+                    Ok(parsed_value) => {
+                        Ok(self.yield_literal_token(Number, Literal::Integer(parsed_value)))
+                    }
+                    Err(_) => Err(LexError::new(
+                        format!("Integer literal {} is too large.", value),
+                        self.line,
+                    )),
+                }
             }
         } else {
-            Err(LexError::new("".into()))
+            Err(LexError::new("".into(), self.line))
+        }
+    }
+
+    // an exponent is an 'e' or 'E' followed by digits, optionally signed
+    fn at_exponent(&self) -> bool {
+        let mut rest = self.source[self.current..].chars();
+        if !matches!(rest.next(), Some('e' | 'E')) {
+            return false;
+        }
+        match rest.next() {
+            Some('+' | '-') => rest.next().is_some_and(|ch| ch.is_ascii_digit()),
+            Some(ch) => ch.is_ascii_digit(),
+            None => false,
         }
     }
 
+    // a backslash keeps the character after it from ending the string, and `\"` stands for
+    // a quote; other backslashes are kept as written
     fn yield_string(&mut self) -> Result<Token, LexError> {
+        let mut escaped = false;
+        let value = self.take_while(|ch| {
+            let in_string = escaped || ch != '"';
+            escaped = !escaped && ch == '\\';
+            in_string
+        });
+        self.finish_quoted_string(value, 1, Lexer::unescape_quotes)
+    }
+
+    // raw strings (r"...") keep every character between the quotes as written
+    fn yield_raw_string(&mut self) -> Result<Token, LexError> {
+        self.advance();
         let value = self.take_while(|ch| ch != '"');
+        self.finish_quoted_string(value, 2, str::to_string)
+    }
+
+    fn finish_quoted_string(
+        &mut self,
+        value: Option<(&'source str, Range<usize>)>,
+        prefix_len: usize,
+        contents: fn(&str) -> String,
+    ) -> Result<Token, LexError> {
+        let terminated = self.peek() == Some('"');
         self.advance();
         if let Some((str_literal, _)) = value {
-            if self.is_at_end() && self.source.chars().last().unwrap() != '"' {
-                panic!("Unterminated string");
+            if !terminated {
+                // a backslash right before the end escapes the closing quote
+                return Err(LexError::new("Unterminated string.".into(), self.line));
             }
-            let token =
-                self.yield_literal_token(SoxString, Literal::String(str_literal[1..].to_string()));
+            let token = self.yield_literal_token(
+                SoxString,
+                Literal::String(contents(&str_literal[prefix_len..])),
+            );
             Ok(token)
         } else {
-            Err(LexError::new("".into()))
+            Err(LexError::new("".into(), self.line))
         }
     }
 
+    fn unescape_quotes(text: &str) -> String {
+        let mut unescaped = String::with_capacity(text.len());
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => match chars.next() {
+                    Some('"') => unescaped.push('"'),
+                    next => {
+                        unescaped.push(ch);
+                        unescaped.extend(next);
+                    }
+                },
+                _ => unescaped.push(ch),
+            }
+        }
+        unescaped
+    }
+
+    // `current` is a byte offset into the source, so a multibyte character is stepped over
+    // as a whole
     fn advance(&mut self) -> Option<char> {
-        let curr_char = self.source.chars().nth(self.current);
-        self.current += 1;
-        return curr_char;
+        let curr_char = self.peek();
+        self.current += curr_char.map_or(1, char::len_utf8);
+        curr_char
     }
 
     fn yield_token(&mut self, token_type: TokenType) -> Token {
@@ -161,30 +289,37 @@ impl<'source> Lexer<'source> {
         if self.peek().unwrap_or('\0') != expected {
             return false;
         }
-        self.current += 1;
+        self.current += expected.len_utf8();
         return true;
     }
 
     fn token_from_result(&self, input: Result<Token, LexError>) -> Option<Token> {
         match input {
             Ok(v) => Some(v),
-            Err(e) => Some(Token::new(
-                TokenType::Error,
-                e.msg.into(),
-                Literal::None,
-                self.line,
-            )),
+            Err(e) => Some(Token::new(TokenType::Error, e.msg, Literal::None, e.line)),
         }
     }
     fn peek(&self) -> Option<char> {
-        return self.source.chars().nth(self.current);
+        self.source.get(self.current..)?.chars().next()
     }
 
     fn peek_next(&self) -> Option<char> {
-        self.source.chars().nth(self.current + 1)
+        self.source.get(self.current..)?.chars().nth(1)
     }
 }
 
+/// Whether a `//` comment is a doc comment: exactly three slashes, as `////` and longer
+/// runs are ordinary comments.
+fn is_doc_comment(comment: &str) -> bool {
+    comment.starts_with("///") && !comment.starts_with("////")
+}
+
+/// The text of a doc comment without its `///` and the space that usually follows it.
+fn doc_comment_text(comment: &str) -> &str {
+    let text = &comment["///".len()..];
+    text.strip_prefix(' ').unwrap_or(text)
+}
+
 impl<'source> Iterator for Lexer<'source> {
     type Item = Token;
 
@@ -198,6 +333,8 @@ impl<'source> Iterator for Lexer<'source> {
                     ')' => Some(self.yield_token(RightParen)),
                     '{' => Some(self.yield_token(LeftBrace)),
                     '}' => Some(self.yield_token(RightBrace)),
+                    '[' => Some(self.yield_token(LeftSqb)),
+                    ']' => Some(self.yield_token(RightSqb)),
                     ',' => Some(self.yield_token(Comma)),
                     '.' => Some(self.yield_token(Dot)),
                     '-' => Some(self.yield_token(Minus)),
@@ -206,6 +343,9 @@ impl<'source> Iterator for Lexer<'source> {
                     ':' => Some(self.yield_token(Colon)),
                     '%' => Some(self.yield_token(Rem)),
                     '*' => Some(self.yield_token(Star)),
+                    '&' => Some(self.yield_token(Ampersand)),
+                    '|' => Some(self.yield_token(Pipe)),
+                    '^' => Some(self.yield_token(Caret)),
                     '!' => {
                         let token = if self.char_matches('=') {
                             BangEqual
@@ -225,6 +365,8 @@ impl<'source> Iterator for Lexer<'source> {
                     '<' => {
                         let token = if self.char_matches('=') {
                             LessEqual
+                        } else if self.char_matches('<') {
+                            LessLess
                         } else {
                             Less
                         };
@@ -233,15 +375,24 @@ impl<'source> Iterator for Lexer<'source> {
                     '>' => {
                         let token = if self.char_matches('=') {
                             GreaterEqual
+                        } else if self.char_matches('>') {
+                            GreaterGreater
                         } else {
                             Greater
                         };
                         Some(self.yield_token(token))
                     }
+                    '?' if self.char_matches('?') => Some(self.yield_token(QuestionQuestion)),
                     '/' => {
                         if self.char_matches('/') {
                             let comment_value = self.take_while(|ch| ch != '\n');
                             match comment_value {
+                                Some((comment, _)) if is_doc_comment(comment) => Some(Token::new(
+                                    TokenType::DocComment,
+                                    comment.to_string(),
+                                    Literal::String(doc_comment_text(comment).to_string()),
+                                    self.line,
+                                )),
                                 Some((comment, _)) => Some(Token::new(
                                     TokenType::Comment,
                                     comment.to_string(),
@@ -296,6 +447,10 @@ impl<'source> Iterator for Lexer<'source> {
                         let sox_string = self.yield_string();
                         self.token_from_result(sox_string)
                     }
+                    'r' if self.peek() == Some('"') => {
+                        let sox_string = self.yield_raw_string();
+                        self.token_from_result(sox_string)
+                    }
                     'A'..='Z' | 'a'..='z' | '_' => {
                         let ident_val = self.yield_identifier();
                         self.token_from_result(ident_val)
@@ -309,7 +464,7 @@ impl<'source> Iterator for Lexer<'source> {
                         debug!("Token -{character} - not in allowed set of valid tokens");
                         Some(Token::new(
                             TokenType::Error,
-                            "Token -{character} - not in allowed set of valid tokens".into(),
+                            format!("Token -{character} - not in allowed set of valid tokens"),
                             Literal::None,
                             self.line,
                         ))
@@ -332,10 +487,40 @@ impl<'source> Iterator for Lexer<'source> {
 
 #[cfg(test)]
 mod tests {
-    use crate::lexer::Lexer;
-    use crate::parser::TO_IGNORE;
-    use crate::token::{Literal, Token};
-    use crate::token_type::TokenType;
+    use crate::lexer::{tokenize, LexError, Lexer};
+    use crate::token::{Float, Literal, Token};
+    use crate::token_type::{TokenType, TO_IGNORE};
+
+    #[test]
+    fn test_extreme_float_lex() {
+        let literals = Lexer::lex("1e400 1e-400 2.5E+3 1e")
+            .filter(|token| token.token_type == TokenType::Number)
+            .map(|token| token.literal)
+            .collect::<Vec<Literal>>();
+        assert_eq!(
+            literals,
+            vec![
+                Literal::Float(Float(f64::INFINITY)),
+                Literal::Float(Float(0.0)),
+                Literal::Float(Float(2500.0)),
+                Literal::Integer(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_oversized_integer_lex() {
+        let tokens = Lexer::lex("99999999999999999999;").collect::<Vec<Token>>();
+        assert_eq!(tokens[0].token_type, TokenType::Error);
+        assert_eq!(
+            tokens[0].lexeme,
+            "Integer literal 99999999999999999999 is too large."
+        );
+        assert_eq!(tokens[1].token_type, TokenType::Semi);
+
+        let tokens = Lexer::lex("9223372036854775807").collect::<Vec<Token>>();
+        assert_eq!(tokens[0].literal, Literal::Integer(i64::MAX));
+    }
 
     #[test]
     fn test_class_lex() {
@@ -362,6 +547,60 @@ class A {
         )
     }
 
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("let x = 1; // one").unwrap();
+        let types = tokens
+            .into_iter()
+            .map(|token| token.token_type)
+            .collect::<Vec<TokenType>>();
+        assert_eq!(
+            types,
+            vec![
+                TokenType::Let,
+                TokenType::Identifier,
+                TokenType::Equal,
+                TokenType::Number,
+                TokenType::Semi,
+            ]
+        );
+
+        let errors = tokenize("let x = 99999999999999999999;\nlet y = 1;").unwrap_err();
+        assert_eq!(
+            errors,
+            vec![LexError::new(
+                "Integer literal 99999999999999999999 is too large.".into(),
+                1
+            )]
+        );
+    }
+
+    #[test]
+    fn test_lex_error_line() {
+        let errors = tokenize("let a = 1;\nlet b = 2;\nlet c = 3 $ 4;").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line(), 3);
+        assert_eq!(
+            errors[0].message(),
+            "Token -$ - not in allowed set of valid tokens"
+        );
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        let errors = tokenize("print 1;\nprint \"abc;").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line(), 2);
+        assert_eq!(errors[0].message(), "Unterminated string.");
+    }
+
+    #[test]
+    fn test_trailing_backslash_escapes_the_closing_quote() {
+        let errors = tokenize("print \"abc\\\";").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message(), "Unterminated string.");
+    }
+
     #[test]
     fn test_var_lex() {
         let source = "let v = 10;";
@@ -394,6 +633,57 @@ def fib(n) {
         assert_eq!(non_whitespace_tokens.len(), 37);
     }
 
+    #[test]
+    fn test_raw_string_lex() {
+        let source = r#"r"\n" r"C:\new""#;
+        let literals = Lexer::lex(source)
+            .filter(|token| token.token_type == TokenType::SoxString)
+            .map(|token| token.literal)
+            .collect::<Vec<Literal>>();
+        assert_eq!(
+            literals,
+            vec![
+                Literal::String("\\n".into()),
+                Literal::String("C:\\new".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escaped_quote_lex() {
+        let source = r#"print "he said \"hi\"" + "C:\\" + "\n";"#;
+        let literals = Lexer::lex(source)
+            .filter(|token| token.token_type == TokenType::SoxString)
+            .map(|token| token.literal)
+            .collect::<Vec<Literal>>();
+        assert_eq!(
+            literals,
+            vec![
+                Literal::String("he said \"hi\"".into()),
+                Literal::String("C:\\\\".into()),
+                Literal::String("\\n".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multibyte_characters_lex() {
+        let tokens = Lexer::lex("let s = \"héllo\";")
+            .filter(|token| token.token_type != TokenType::Whitespace)
+            .map(|token| (token.token_type, token.lexeme))
+            .collect::<Vec<(TokenType, String)>>();
+        assert_eq!(
+            tokens,
+            vec![
+                (TokenType::Let, "let".to_string()),
+                (TokenType::Identifier, "s".to_string()),
+                (TokenType::Equal, "=".to_string()),
+                (TokenType::SoxString, "\"héllo\"".to_string()),
+                (TokenType::Semi, ";".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_line_numbers() {
         let source = r#"/*
@@ -426,4 +716,22 @@ for (let i=0; i < 10; i=i+1){
             }
         }
     }
+
+    #[test]
+    fn test_doc_comment_lex() {
+        let comments = Lexer::lex("/// hello\n// plain\n//// ruler\n")
+            .filter(|token| {
+                matches!(token.token_type, TokenType::Comment | TokenType::DocComment)
+            })
+            .map(|token| (token.token_type, token.literal, token.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            vec![
+                (TokenType::DocComment, Literal::String("hello".into()), 1),
+                (TokenType::Comment, Literal::String("// plain".into()), 2),
+                (TokenType::Comment, Literal::String("//// ruler".into()), 3),
+            ]
+        );
+    }
 }
@@ -1,3 +1,5 @@
+#[cfg(feature = "serde")]
+pub mod ast_json;
 pub mod expr;
 pub mod lexer;
 pub mod parser;
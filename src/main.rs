@@ -1,7 +1,8 @@
-use std::io::Write;
 use std::env;
+use std::io::{self, IsTerminal, Write};
 
 use log::LevelFilter;
+use sox::init::{ColorScheme, WarningMode};
 
 fn main() {
     env_logger::Builder::new()
@@ -19,14 +20,37 @@ fn main() {
         .filter(None, LevelFilter::Debug)
         .init();
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() >= 2 {
+    let args: Vec<String> = env::args().skip(1).collect();
+    // `--strict` and `-W error` both promote resolver warnings to errors
+    let strict = args.iter().any(|arg| arg == "--strict")
+        || args
+            .windows(2)
+            .any(|pair| pair[0] == "-W" && pair[1] == "error");
+    let warning_mode = if strict {
+        WarningMode::Error
+    } else {
+        WarningMode::Print
+    };
+    let script = args
+        .iter()
+        .enumerate()
+        .find(|(idx, arg)| !arg.starts_with('-') && (*idx == 0 || args[idx - 1] != "-W"))
+        .map(|(_, arg)| arg.to_string());
+    if let Some(script) = script {
         //println!("Usage: sox [script]");
         // 64 is the exit code used when args passed to a script are incorrect
         //process::exit(64);
         //} else if args.len() == 2 {
-        sox::init::run_file(args.get(1).unwrap().to_string());
+        // like the REPL, the value of the script's last statement is echoed
+        match sox::init::run_file(script, warning_mode) {
+            Ok(Some(value)) => println!("{}", value),
+            Ok(None) => {}
+            Err(error) => println!("{}", error),
+        }
     } else {
-        sox::init::run_prompt();
+        // echoed values are colored unless disabled or the output is redirected
+        let colors = (!args.iter().any(|arg| arg == "--no-color") && io::stdout().is_terminal())
+            .then(ColorScheme::default);
+        sox::init::run_prompt(colors);
     }
 }
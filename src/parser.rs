@@ -1,32 +1,52 @@
 use std::iter::Peekable;
 
 
-use crate::expr::Expr;
+use crate::expr::{Expr, VarRef};
 use crate::stmt::Stmt;
 use crate::token::{Literal, Token};
-use crate::token_type::TokenType;
+use crate::token_type::{TokenType, TO_IGNORE};
 use crate::token_type::TokenType::{
-    And, Bang, BangEqual, Class, Colon, Comma, Def, Dot, Else, Equal, EqualEqual, False, For,
-    Greater, GreaterEqual, Identifier, If, LeftBrace, LeftParen, Less, LessEqual, Let, Minus,
-    Number, Or, Plus, Print, Rem, Return, RightBrace, RightParen, Semi, Slash, SoxString, Star,
-    Super, This, True, While,
+    Ampersand, And, Bang, BangEqual, Caret, Case, Catch, Class, Colon, Comma, Const, Continue, Def,
+    Default, Dot, Else, Equal, EqualEqual, False, For, Greater, GreaterEqual, GreaterGreater,
+    Identifier, If, Is, LeftBrace, LeftParen, LeftSqb, Less, LessEqual, LessLess, Let, Match,
+    Minus, Not, Number, Or, Pipe, Plus, Print, QuestionQuestion, Rem, Return, RightBrace,
+    RightParen, RightSqb, Semi, Slash, SoxString, Star, Super, This, Throw, True, Try, While,
 };
 
-pub static TO_IGNORE: &'static [TokenType] = &[
-    TokenType::Comment,
-    TokenType::Whitespace,
-    TokenType::Newline,
-];
-
 pub struct Parser<I: Iterator<Item = Token>> {
     tokens: Peekable<I>,
     processed_tokens: Vec<Token>,
 }
 
+/// An error found while parsing, rendered as `[line N] msg` by its `Display` impl.
 #[derive(Clone, Debug)]
 pub struct SyntaxError {
     msg: String,
     line: usize,
+    at_eof: bool,
+}
+
+impl SyntaxError {
+    /// The line the error was found on.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The error message, e.g. `Error at ';': Expect an expression.`.
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    /// Whether the parser ran out of tokens, i.e. the source may just be incomplete.
+    pub fn at_eof(&self) -> bool {
+        self.at_eof
+    }
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[line {}] {}", self.line, self.msg)
+    }
 }
 
 impl<I: Iterator<Item = Token>> Parser<I> {
@@ -49,11 +69,8 @@ impl<I: Iterator<Item = Token>> Parser<I> {
             let stmt = self.declaration();
             if let Ok(val) = stmt {
                 statements.push(val);
-            } else {
-                if let Err(e) = stmt {
-                    println!("[line {}] {}", e.line, e.msg.to_string());
-                    errors.push(e);
-                }
+            } else if let Err(e) = stmt {
+                errors.push(e);
             }
         }
         if errors.is_empty() {
@@ -70,7 +87,7 @@ impl<I: Iterator<Item = Token>> Parser<I> {
             }
             let peek_val = self.tokens.peek();
             if peek_val.is_some()
-                && vec![Class, Def, Let, For, If, While, Print, Return]
+                && vec![Class, Def, Let, Const, For, If, While, Print, Return, Throw, Try, Match]
                     .contains(&peek_val.unwrap().token_type)
             {
                 return;
@@ -86,6 +103,8 @@ impl<I: Iterator<Item = Token>> Parser<I> {
             self.function("function".into())
         } else if self.match_token(vec![Let]) {
             self.var_declaration()
+        } else if self.match_token(vec![Const]) {
+            self.const_declaration()
         } else {
             self.statement()
         };
@@ -102,42 +121,97 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         if self.match_token(vec![Colon]) {
             let _ = self.consume(Identifier, "Expect a superclass name".into())?;
             let prev = self.previous();
-            super_class = Some(Expr::Variable { name: prev });
+            super_class = Some(Expr::Variable {
+                name: prev,
+                var: VarRef::next(),
+            });
         }
         let _ = self.consume(LeftBrace, "Expect '{' before class body".into())?;
         let mut methods = vec![];
+        let mut overrides = vec![];
+        let mut getters = vec![];
         while !self.check(RightBrace) && !self.at_end() {
-            methods.push(self.function("method".into())?);
+            let mut method_name = self.consume(Identifier, "Expect function name".into())?;
+            // like `get` below, `override` only marks a method when a name follows it
+            let is_override = method_name.lexeme == "override" && self.check(Identifier);
+            if is_override {
+                method_name = self.consume(Identifier, "Expect function name".into())?;
+            }
+            // `get area()` declares a getter, while `get()` is an ordinary method named get
+            let is_getter = method_name.lexeme == "get" && self.check(Identifier);
+            if is_getter {
+                method_name = self.consume(Identifier, "Expect function name".into())?;
+            }
+            let method = self.finish_function(method_name.clone())?;
+            if let (true, Stmt::Function { params, .. }) = (is_getter, &method) {
+                if let Some(param) = params.first() {
+                    return Err(SyntaxError {
+                        msg: format!(
+                            "Error at '{}': A getter can't take parameters.",
+                            param.lexeme
+                        ),
+                        line: param.line,
+                        at_eof: false,
+                    });
+                }
+                getters.push(method_name.clone());
+            }
+            if is_override {
+                overrides.push(method_name);
+            }
+            methods.push(method);
         }
-        let _ = self.consume(RightBrace, "Expect '}' after class body.".into())?;
+        let _ = self.consume(RightBrace, "Expect '}' after class body".into())?;
         let class = Stmt::Class {
             name,
             methods,
+            overrides,
+            getters,
             superclass: super_class,
         };
         return Ok(class);
     }
 
     fn function(&mut self, _kind: String) -> Result<Stmt, SyntaxError> {
-        let name = self.consume(Identifier, "Expect function name.".into())?;
-        let _ = self.consume(LeftParen, "Expect '(' after function name.".into())?;
+        let name = self.consume(Identifier, "Expect function name".into())?;
+        self.finish_function(name)
+    }
+
+    // parses the parameters and body of a function whose name has been consumed
+    fn finish_function(&mut self, name: Token) -> Result<Stmt, SyntaxError> {
+        let _ = self.consume(LeftParen, "Expect '(' after function name".into())?;
         let mut params: Vec<Token> = vec![];
+        let mut defaults: Vec<Option<Expr>> = vec![];
         if !self.check(RightParen) {
             loop {
                 if params.len() >= 255 {
-                    while self.tokens.peek().unwrap().lexeme == " " {
-                        self.tokens.next();
-                    }
+                    let token = self.peek().unwrap();
                     return Err(SyntaxError {
                         msg: format!(
-                            "Error at '{}'. Can't have more than 255 parameters.",
-                            self.tokens.peek().unwrap().lexeme
+                            "Error at '{}': Can't have more than 255 parameters.",
+                            token.lexeme
                         ),
-                        line: name.line,
+                        line: token.line,
+                        at_eof: false,
                     });
                 }
-                let param = self.consume(Identifier, "Expect parameter name.".into())?;
+                let param = self.consume(Identifier, "Expect parameter name".into())?;
+                let default = if self.match_token(vec![Equal]) {
+                    Some(self.expression()?)
+                } else if defaults.iter().any(|d| d.is_some()) {
+                    return Err(SyntaxError {
+                        msg: format!(
+                            "Error at '{}': Non-default parameter follows default parameter.",
+                            param.lexeme
+                        ),
+                        line: param.line,
+                        at_eof: false,
+                    });
+                } else {
+                    None
+                };
                 params.push(param);
+                defaults.push(default);
 
                 if !self.match_token(vec![Comma]) {
                     break;
@@ -148,12 +222,17 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         let _ = self.consume(LeftBrace, "Expect '{' before function body".into())?;
 
         let body = self.block()?;
-        let stmt = Stmt::Function { name, params, body };
+        let stmt = Stmt::Function {
+            name,
+            params,
+            defaults,
+            body,
+        };
         Ok(stmt)
     }
 
     fn var_declaration(&mut self) -> Result<Stmt, SyntaxError> {
-        let name = self.consume(Identifier, "Expect variable name.".into())?;
+        let name = self.consume(Identifier, "Expect variable name".into())?;
         let mut initializer = None;
         if self.match_token(vec![Equal]) {
             initializer = Some(self.expression()?);
@@ -162,6 +241,14 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         Ok(Stmt::Var { name, initializer })
     }
 
+    fn const_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        let name = self.consume(Identifier, "Expect constant name".into())?;
+        let _ = self.consume(Equal, "Expect '=' after constant name".into())?;
+        let initializer = self.expression()?;
+        let _ = self.consume(Semi, "Expect ';' after constant declaration".into())?;
+        Ok(Stmt::Const { name, initializer })
+    }
+
     fn statement(&mut self) -> Result<Stmt, SyntaxError> {
         if self.match_token(vec![For]) {
             return self.for_statement();
@@ -178,6 +265,18 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         if self.match_token(vec![Return]) {
             return self.return_statement();
         }
+        if self.match_token(vec![Continue]) {
+            return self.continue_statement();
+        }
+        if self.match_token(vec![Throw]) {
+            return self.throw_statement();
+        }
+        if self.match_token(vec![Try]) {
+            return self.try_statement();
+        }
+        if self.match_token(vec![Match]) {
+            return self.match_statement();
+        }
         if self.match_token(vec![LeftBrace]) {
             let block_statements = self.block()?;
             return Ok(Stmt::Block(block_statements));
@@ -185,19 +284,119 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         return self.expression_statement();
     }
 
+    fn continue_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous();
+        let _ = self.consume(Semi, "Expect ';' after 'continue'".into())?;
+        Ok(Stmt::Continue { keyword })
+    }
+
     fn return_statement(&mut self) -> Result<Stmt, SyntaxError> {
         let keyword = self.previous();
-        let mut value =None; 
-        if !self.check(Semi) {
-            value = Some(self.expression()?);
-        }
-        let _ = self.consume(Semi, "Expect ';' after return value.".into())?;
+        let value = if self.check(Semi) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        let _ = self.consume(Semi, "Expect ';' after return value".into())?;
         let return_stmt = Stmt::Return { keyword, value };
         return Ok(return_stmt);
     }
 
+    fn throw_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+        let _ = self.consume(Semi, "Expect ';' after thrown value".into())?;
+        Ok(Stmt::Throw { keyword, value })
+    }
+
+    fn try_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let _ = self.consume(LeftBrace, "Expect '{' after 'try'".into())?;
+        let body = self.block()?;
+        let _ = self.consume(Catch, "Expect 'catch' after try block".into())?;
+        let _ = self.consume(LeftParen, "Expect '(' after 'catch'".into())?;
+        let catch_name = self.consume(Identifier, "Expect exception variable name".into())?;
+        let _ = self.consume(RightParen, "Expect ')' after exception variable".into())?;
+        let _ = self.consume(LeftBrace, "Expect '{' before catch body".into())?;
+        let catch_body = self.block()?;
+        Ok(Stmt::Try {
+            body,
+            catch_name,
+            catch_body,
+        })
+    }
+
+    fn match_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let _ = self.consume(LeftParen, "Expect '(' after 'match'".into())?;
+        let subject = self.expression()?;
+        let _ = self.consume(RightParen, "Expect ')' after match subject".into())?;
+        let _ = self.consume(LeftBrace, "Expect '{' before match cases".into())?;
+        let mut cases = vec![];
+        let mut default = None;
+        while !self.check(RightBrace) && !self.at_end() {
+            match self.case_label(default.is_some()) {
+                Ok(Some(value)) => cases.push((value, self.case_body()?)),
+                Ok(None) => default = Some(self.case_body()?),
+                Err(err) => {
+                    // skip the rest of the match so a bad case is reported once
+                    self.skip_to_match_end();
+                    return Err(err);
+                }
+            }
+        }
+        let _ = self.consume(RightBrace, "Expect '}' after match cases".into())?;
+        Ok(Stmt::Match {
+            subject,
+            cases,
+            default,
+        })
+    }
+
+    // parses `case value:` or `default:`, giving the case value or None for the default
+    fn case_label(&mut self, has_default: bool) -> Result<Option<Expr>, SyntaxError> {
+        if self.match_token(vec![Default]) {
+            let keyword = self.previous();
+            if has_default {
+                return Err(SyntaxError {
+                    msg: "Error at 'default': A match can only have one default case.".into(),
+                    line: keyword.line,
+                    at_eof: false,
+                });
+            }
+            let _ = self.consume(Colon, "Expect ':' after 'default'".into())?;
+            return Ok(None);
+        }
+        let _ = self.consume(Case, "Expect 'case' or 'default' in match body".into())?;
+        let value = self.expression()?;
+        let _ = self.consume(Colon, "Expect ':' after case value".into())?;
+        Ok(Some(value))
+    }
+
+    // stops before the '}' closing the match, which synchronize then steps over
+    fn skip_to_match_end(&mut self) {
+        let mut depth = 0;
+        while !self.at_end() {
+            match self.tokens.peek().map(|token| token.token_type) {
+                Some(RightBrace) if depth == 0 => return,
+                Some(RightBrace) => depth -= 1,
+                Some(LeftBrace) => depth += 1,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    // the statements of a case, which run up to the next case or the end of the match
+    fn case_body(&mut self) -> Result<Vec<Stmt>, SyntaxError> {
+        let mut statements = vec![];
+        while !self.check(Case) && !self.check(Default) && !self.check(RightBrace) && !self.at_end()
+        {
+            statements.push(self.declaration()?);
+        }
+        Ok(statements)
+    }
+
     fn for_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        let _ = self.consume(LeftParen, "Expect '(' after 'for'.".to_string())?;
+        let _ = self.consume(LeftParen, "Expect '(' after 'for'".to_string())?;
         let initializer;
         if self.match_token(vec![Semi]) {
             initializer = None;
@@ -210,36 +409,25 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         if !self.check(Semi) {
             condition = Some(self.expression()?);
         }
-        let _ = self.consume(Semi, "Expect ';' after loop condition.".to_string())?;
+        let _ = self.consume(Semi, "Expect ';' after loop condition".to_string())?;
         let mut increment = None;
         if !self.check(RightParen) {
             increment = Some(self.expression()?);
         }
-        let _ = self.consume(RightParen, "Expect ')' after for clauses.".to_string())?;
-        let mut body = self.statement()?;
-        if let Some(inc) = increment {
-            let stmts = vec![body, Stmt::Expression(inc)];
-            body = Stmt::Block(stmts)
-        }
-        if condition.is_none() {
-            condition = Some(Expr::Literal {
-                value: Literal::Boolean(true),
-            });
-        }
-        body = Stmt::While {
-            condition: condition.unwrap(),
+        let _ = self.consume(RightParen, "Expect ')' after for clauses".to_string())?;
+        let body = self.statement()?;
+        Ok(Stmt::For {
+            initializer: initializer.map(Box::new),
+            condition,
+            increment,
             body: Box::new(body),
-        };
-        if let Some(init) = initializer {
-            body = Stmt::Block(vec![init, body])
-        }
-        Ok(body)
+        })
     }
 
     fn while_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        let _ = self.consume(LeftParen, "Expect '(' after 'while'.".into())?;
+        let _ = self.consume(LeftParen, "Expect '(' after 'while'".into())?;
         let condition = self.expression()?;
-        let _ = self.consume(RightParen, "Expect ')' after 'while' condition.".into())?;
+        let _ = self.consume(RightParen, "Expect ')' after 'while' condition".into())?;
         let body = self.statement()?;
         Ok(Stmt::While {
             condition,
@@ -248,9 +436,9 @@ impl<I: Iterator<Item = Token>> Parser<I> {
     }
 
     fn if_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        let _ = self.consume(LeftParen, "Expect '(' after 'if'.".into())?;
+        let _ = self.consume(LeftParen, "Expect '(' after 'if'".into())?;
         let condition = self.expression()?;
-        let _ = self.consume(RightParen, "Expect ')' after 'if' condition.".into())?;
+        let _ = self.consume(RightParen, "Expect ')' after 'if' condition".into())?;
 
         let then_branch = self.statement()?;
         let mut else_branch = None;
@@ -285,23 +473,27 @@ impl<I: Iterator<Item = Token>> Parser<I> {
     }
 
     fn print_statement(&mut self) -> Result<Stmt, SyntaxError> {
-        let value = self.expression();
-        if let Ok(v) = value {
-            let _ = self.consume(Semi, "Expect ';' after expression.".into())?;
-            Ok(Stmt::Print(v))
-        } else {
-            Err(value.err().unwrap())
+        let mut values = vec![];
+        if !self.check(Semi) {
+            values.push(self.expression()?);
+            while self.match_token(vec![Comma]) {
+                values.push(self.expression()?);
+            }
         }
+        let _ = self.consume(Semi, "Expect ';' after expression".into())?;
+        Ok(Stmt::Print(values))
     }
 
     fn expression(&mut self) -> Result<Expr, SyntaxError> {
-        let expr = self.or()?;
+        let expr = self.coalesce()?;
         if self.match_token(vec![Equal]) {
+            let equals = self.previous();
             let value = self.expression()?;
-            if let Expr::Variable { name } = expr {
+            if let Expr::Variable { name, var } = expr {
                 return Ok(Expr::Assign {
                     name,
                     value: Box::new(value),
+                    var,
                 });
             } else if let Expr::Get { name, object } = expr {
                 return Ok(Expr::Set {
@@ -309,6 +501,38 @@ impl<I: Iterator<Item = Token>> Parser<I> {
                     name,
                     value: Box::new(value),
                 });
+            } else if let Expr::Index {
+                object,
+                bracket,
+                index,
+            } = expr
+            {
+                return Ok(Expr::SetIndex {
+                    object,
+                    bracket,
+                    index,
+                    value: Box::new(value),
+                });
+            }
+            return Err(SyntaxError {
+                msg: "Error at '=': Invalid assignment target.".into(),
+                line: equals.line,
+                at_eof: false,
+            });
+        }
+        Ok(expr)
+    }
+
+    // `a ?? b` is `a` unless that is None, in which case it's `b`
+    fn coalesce(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.or()?;
+        while self.match_token(vec![QuestionQuestion]) {
+            let operator = self.previous();
+            let right = self.or()?;
+            expr = Expr::Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
             }
         }
         Ok(expr)
@@ -329,10 +553,10 @@ impl<I: Iterator<Item = Token>> Parser<I> {
     }
 
     fn and(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.equality()?;
+        let mut expr = self.not()?;
         while self.match_token(vec![And]) {
             let operator = self.previous();
-            let right = self.equality()?;
+            let right = self.not()?;
             expr = Expr::Logical {
                 left: Box::new(expr),
                 operator,
@@ -341,10 +565,96 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         }
         return Ok(expr);
     }
+
+    // `not` binds looser than equality and comparison but tighter than `and`, so
+    // `not a == b` groups as `not (a == b)` and `not a and b` as `(not a) and b`. `!` stays
+    // a unary operator that binds tighter than any binary operator.
+    fn not(&mut self) -> Result<Expr, SyntaxError> {
+        if self.match_token(vec![Not]) {
+            let operator = self.previous();
+            let right = self.not()?;
+            return Ok(Expr::Unary {
+                operator,
+                right: Box::new(right),
+            });
+        }
+        self.equality()
+    }
+
+    // Bitwise operators bind tighter than equality and looser than comparison, from loosest
+    // to tightest: `|`, `^`, `&`. Shifts bind tighter than comparison but looser than `+`
+    // and `-`, so `1 << 2 + 1 < 10` groups as `(1 << (2 + 1)) < 10`.
+    fn bitwise_or(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.bitwise_xor()?;
+        while self.match_token(vec![Pipe]) {
+            let operator = self.previous();
+            let right = self.bitwise_xor()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn bitwise_xor(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.bitwise_and()?;
+        while self.match_token(vec![Caret]) {
+            let operator = self.previous();
+            let right = self.bitwise_and()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn bitwise_and(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.comparison()?;
+        while self.match_token(vec![Ampersand]) {
+            let operator = self.previous();
+            let right = self.comparison()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    // `a < b < c` would compare the bool `a < b` with `c`, so comparisons can't be chained
     fn comparison(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.term()?;
+        let expr = self.shift()?;
+        if !self.match_token(vec![Greater, GreaterEqual, Less, LessEqual]) {
+            return Ok(expr);
+        }
+        let operator = self.previous();
+        let right = self.shift()?;
+        if self.match_token(vec![Greater, GreaterEqual, Less, LessEqual]) {
+            let chained = self.previous();
+            return Err(SyntaxError {
+                msg: format!(
+                    "Error at '{}': Chained comparisons are not supported; combine them with 'and'.",
+                    chained.lexeme
+                ),
+                line: chained.line,
+                at_eof: false,
+            });
+        }
+        Ok(Expr::Binary {
+            left: Box::new(expr),
+            operator,
+            right: Box::new(right),
+        })
+    }
 
-        while self.match_token(vec![Greater, GreaterEqual, Less, LessEqual]) {
+    fn shift(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.term()?;
+        while self.match_token(vec![LessLess, GreaterGreater]) {
             let operator = self.previous();
             let right = self.term()?;
             expr = Expr::Binary {
@@ -353,7 +663,7 @@ impl<I: Iterator<Item = Token>> Parser<I> {
                 right: Box::new(right),
             };
         }
-        return Ok(expr);
+        Ok(expr)
     }
 
     fn term(&mut self) -> Result<Expr, SyntaxError> {
@@ -409,6 +719,8 @@ impl<I: Iterator<Item = Token>> Parser<I> {
                     object: Box::new(expr),
                     name,
                 }
+            } else if self.match_token(vec![LeftSqb]) {
+                expr = self.finish_subscript(expr)?;
             } else {
                 break;
             }
@@ -416,14 +728,48 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         Ok(expr)
     }
 
+    fn finish_subscript(&mut self, object: Expr) -> Result<Expr, SyntaxError> {
+        let bracket = self.previous();
+        let start = if self.check(Colon) {
+            None
+        } else {
+            Some(Box::new(self.expression()?))
+        };
+        if !self.match_token(vec![Colon]) {
+            self.consume(RightSqb, "Expect ']' after index".into())?;
+            return Ok(Expr::Index {
+                object: Box::new(object),
+                bracket,
+                index: start.expect("an index without a ':' was parsed"),
+            });
+        }
+        let end = if self.check(RightSqb) {
+            None
+        } else {
+            Some(Box::new(self.expression()?))
+        };
+        self.consume(RightSqb, "Expect ']' after slice".into())?;
+        Ok(Expr::Slice {
+            object: Box::new(object),
+            bracket,
+            start,
+            end,
+        })
+    }
+
     fn finish_call(&mut self, callee: Expr) -> Result<Expr, SyntaxError> {
         let mut arguments = vec![];
         if !self.check(RightParen) {
             loop {
                 if arguments.len() >= 255 {
+                    let token = self.peek().unwrap();
                     return Err(SyntaxError {
-                        msg: "Function cannot have more than 255 arguments".to_string(),
-                        line: self.previous().line,
+                        msg: format!(
+                            "Error at '{}': Can't have more than 255 arguments.",
+                            token.lexeme
+                        ),
+                        line: token.line,
+                        at_eof: false,
                     });
                 }
                 arguments.push(self.expression()?);
@@ -432,7 +778,7 @@ impl<I: Iterator<Item = Token>> Parser<I> {
                 }
             }
         }
-        let paren = self.consume(RightParen, "Expect ')' after arguments.".into())?;
+        let paren = self.consume(RightParen, "Expect ')' after arguments".into())?;
         Ok(Expr::Call {
             callee: Box::new(callee),
             paren,
@@ -440,6 +786,48 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         })
     }
 
+    fn finish_list(&mut self) -> Result<Expr, SyntaxError> {
+        let mut elements = vec![];
+        if !self.check(RightSqb) {
+            loop {
+                elements.push(self.expression()?);
+                if !(self.match_token(vec![Comma])) {
+                    break;
+                }
+            }
+        }
+        let _ = self.consume(RightSqb, "Expect ']' after list elements".into())?;
+        Ok(Expr::List { elements })
+    }
+
+    // `{` in expression position starts a block whose value is a trailing expression
+    // without a semicolon; blocks in statement position are parsed by `statement` instead
+    fn finish_block_expression(&mut self) -> Result<Expr, SyntaxError> {
+        let mut statements = vec![];
+        let mut value = None;
+        while !self.check(RightBrace) && !self.at_end() {
+            let starts_statement = self.tokens.peek().is_some_and(|token| {
+                [
+                    Class, Def, Let, Const, For, If, While, Print, Return, Throw, Try, LeftBrace,
+                ]
+                .contains(&token.token_type)
+            });
+            if starts_statement {
+                statements.push(self.declaration()?);
+                continue;
+            }
+            let expr = self.expression()?;
+            if self.check(RightBrace) {
+                value = Some(Box::new(expr));
+                break;
+            }
+            let _ = self.consume(Semi, "Expect ';' after expression".into())?;
+            statements.push(Stmt::Expression(expr));
+        }
+        let _ = self.consume(RightBrace, "Expect '}' after block".into())?;
+        Ok(Expr::Block { statements, value })
+    }
+
     fn primary(&mut self) -> Result<Expr, SyntaxError> {
         if self.match_token(vec![TokenType::None]) {
             return Ok(Expr::Literal {
@@ -462,38 +850,56 @@ impl<I: Iterator<Item = Token>> Parser<I> {
             let keyword = self.previous();
             let _ = self.consume(Dot, "Expect '.' after 'super'".into())?;
             let method = self.consume(Identifier, "Expect superclass method name".into())?;
-            return Ok(Expr::Super { keyword, method });
+            return Ok(Expr::Super {
+                keyword,
+                method,
+                var: VarRef::next(),
+            });
         } else if self.match_token(vec![This]) {
             return Ok(Expr::This {
                 keyword: self.previous(),
+                var: VarRef::next(),
             });
         } else if self.match_token(vec![Identifier]) {
             return Ok(Expr::Variable {
                 name: self.previous(),
+                var: VarRef::next(),
             });
         } else if self.match_token(vec![LeftParen]) {
             let expr = self.expression()?;
-            let _ = self.consume(RightParen, "Expect ')' after expression.".into())?;
+            let _ = self.consume(RightParen, "Expect ')' after expression".into())?;
             return Ok(Expr::Grouping {
                 expr: Box::new(expr),
             });
+        } else if self.match_token(vec![LeftSqb]) {
+            return self.finish_list();
+        } else if self.match_token(vec![LeftBrace]) {
+            return self.finish_block_expression();
         }
         let token = self.tokens.peek();
+        if let Some(token) = token.filter(|token| token.token_type == TokenType::Error) {
+            return Err(Self::lex_error(token));
+        }
 
-        Err(SyntaxError {
-            msg: format!(
-                "Error at '{}': Expect an expression.",
-                token.unwrap().lexeme
-            ),
-            line: token.unwrap().line,
-        })
+        match token {
+            Some(token) => Err(SyntaxError {
+                msg: format!("Error at '{}': Expect an expression.", token.lexeme),
+                line: token.line,
+                at_eof: false,
+            }),
+            None => Err(SyntaxError {
+                msg: "Error at 'eof': Expect an expression.".to_string(),
+                line: self.processed_tokens.last().map_or(1, |t| t.line),
+                at_eof: true,
+            }),
+        }
     }
 
     fn equality(&mut self) -> Result<Expr, SyntaxError> {
-        let mut expr = self.comparison()?;
-        while self.match_token(vec![BangEqual, EqualEqual]) {
+        let mut expr = self.bitwise_or()?;
+        while self.match_token(vec![BangEqual, EqualEqual, Is]) {
             let operator = self.previous();
-            let right = self.comparison()?;
+            let right = self.bitwise_or()?;
             expr = Expr::Binary {
                 left: Box::new(expr),
                 operator,
@@ -508,16 +914,30 @@ impl<I: Iterator<Item = Token>> Parser<I> {
             let token = self.advance();
             return Ok(token.unwrap());
         }
-        let token_name = self
-            .tokens
-            .peek()
-            .map_or("eof".to_string(), |v| v.lexeme.to_string());
+        // report the error on the line of the token it is about
+        let (token_name, line) = match self.tokens.peek() {
+            Some(token) if token.token_type == TokenType::Error => {
+                return Err(Self::lex_error(token));
+            }
+            Some(token) => (token.lexeme.to_string(), Some(token.line)),
+            None => ("eof".to_string(), None),
+        };
         Err(SyntaxError {
             msg: format!("Error at '{}': {}.", token_name, message),
-            line: self.previous().line,
+            at_eof: line.is_none(),
+            line: line.unwrap_or_else(|| self.previous().line),
         })
     }
 
+    // the lexer reports malformed input as error tokens carrying the message and its line
+    fn lex_error(token: &Token) -> SyntaxError {
+        SyntaxError {
+            msg: format!("Error: {}", token.lexeme),
+            line: token.line,
+            at_eof: false,
+        }
+    }
+
     fn match_token(&mut self, token_types: Vec<TokenType>) -> bool {
         for token_type in token_types {
             if self.check(token_type) {
@@ -559,13 +979,21 @@ impl<I: Iterator<Item = Token>> Parser<I> {
         None
     }
 
-    fn at_end(&mut self) -> bool {
-        let mut token = self.tokens.peek();
-        while token.is_some() && TO_IGNORE.contains(&token.unwrap().token_type) {
+    // the next token, after stepping over whitespace and comments
+    fn peek(&mut self) -> Option<&Token> {
+        while self
+            .tokens
+            .peek()
+            .is_some_and(|token| TO_IGNORE.contains(&token.token_type))
+        {
             let _ = self.tokens.next();
-            token = self.tokens.peek();
         }
-        token.map_or(true, |t| vec![TokenType::EOF].contains(&t.token_type))
+        self.tokens.peek()
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.peek()
+            .map_or(true, |t| vec![TokenType::EOF].contains(&t.token_type))
     }
 }
 
@@ -574,11 +1002,48 @@ mod tests {
     use crate::expr::Expr;
     use crate::lexer::Lexer;
     use crate::parser::Parser;
-    use crate::stmt::Stmt::{Function, Print};
+    use crate::stmt::Stmt::{Expression, Function, Print, Return};
     use crate::token::Literal;
     use crate::token::Token;
     use crate::token_type::TokenType::Identifier;
 
+    #[test]
+    fn test_syntax_error_details() {
+        let tokens = Lexer::lex("let a = 1;\nlet b = ;");
+        let errors = Parser::new(tokens).parse().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line(), 2);
+        assert_eq!(errors[0].message(), "Error at ';': Expect an expression.");
+        assert_eq!(
+            errors[0].to_string(),
+            "[line 2] Error at ';': Expect an expression."
+        );
+    }
+
+    #[test]
+    fn test_lex_error_details() {
+        let tokens = Lexer::lex("let a = 1;\nlet b = 2;\nlet c = 3 $ 4;");
+        let errors = Parser::new(tokens).parse().unwrap_err();
+        assert_eq!(errors[0].line(), 3);
+        assert_eq!(
+            errors[0].message(),
+            "Error: Token -$ - not in allowed set of valid tokens"
+        );
+    }
+
+    #[test]
+    fn test_chained_comparison_is_rejected() {
+        let errors = Parser::new(Lexer::lex("print 1 < 2 < 3;")).parse().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message(),
+            "Error at '<': Chained comparisons are not supported; combine them with 'and'."
+        );
+
+        assert!(Parser::new(Lexer::lex("print 1 < 2 and 2 < 3;")).parse().is_ok());
+        assert!(Parser::new(Lexer::lex("print (1 < 2) == (2 < 3);")).parse().is_ok());
+    }
+
     #[test]
     fn test_function_statement() {
         let source = r#"
@@ -601,9 +1066,10 @@ def hello_world(){
                 id: 0,
             },
             params: vec![],
-            body: vec![Print(Expr::Literal {
+            defaults: vec![],
+            body: vec![Print(vec![Expr::Literal {
                 value: Literal::String("hello world".into()),
-            })],
+            }])],
         }];
         assert_eq!(parse_tree.unwrap(), expected_stmts);
     }
@@ -665,4 +1131,128 @@ for (let i=0; i < 10; i=i+1){
         let parse_tree = parser.parse();
         assert_eq!(parse_tree.is_err(), false);
     }
+
+    #[test]
+    fn test_default_parameters() {
+        let source = r#"
+def add(a, b = 10) {
+   return a + b;
+}"#;
+        let tokens = Lexer::lex(source);
+        let mut parser = Parser::new(tokens);
+
+        let parse_tree = parser.parse().unwrap();
+        if let Function { defaults, .. } = &parse_tree[0] {
+            assert_eq!(defaults.len(), 2);
+            assert!(defaults[0].is_none());
+            assert_eq!(
+                defaults[1],
+                Some(Expr::Literal {
+                    value: Literal::Integer(10)
+                })
+            );
+        } else {
+            panic!("expected a function statement");
+        }
+    }
+
+    #[test]
+    fn test_non_default_parameter_after_default() {
+        let source = r#"
+def add(a = 1, b) {
+   return a + b;
+}"#;
+        let tokens = Lexer::lex(source);
+        let mut parser = Parser::new(tokens);
+
+        let parse_tree = parser.parse();
+        assert_eq!(parse_tree.is_err(), true);
+    }
+
+    #[test]
+    fn test_return_values() {
+        let source = r#"
+def f() {
+   return;
+   return 5;
+}"#;
+        let tokens = Lexer::lex(source);
+        let parse_tree = Parser::new(tokens).parse().unwrap();
+        if let Function { body, .. } = &parse_tree[0] {
+            assert!(matches!(&body[0], Return { value: None, .. }));
+            assert!(matches!(
+                &body[1],
+                Return {
+                    value: Some(Expr::Literal {
+                        value: Literal::Integer(5)
+                    }),
+                    ..
+                }
+            ));
+        } else {
+            panic!("expected a function statement");
+        }
+    }
+
+    #[test]
+    fn test_syntax_errors_report_offending_line() {
+        let cases = [
+            ("print 1\nprint 2;", 2, "Error at 'print': Expect ';' after expression."),
+            ("let a = 1;\n\nlet = 2;", 3, "Error at '=': Expect variable name."),
+            ("print (1 +\n2\n;", 3, "Error at ';': Expect ')' after expression."),
+        ];
+        for (source, line, message) in cases {
+            let errors = Parser::new(Lexer::lex(source)).parse().unwrap_err();
+            assert_eq!(errors[0].line(), line, "{}", source);
+            assert_eq!(errors[0].message(), message, "{}", source);
+        }
+    }
+
+    /// Renders an expression with every operation parenthesized, to make grouping visible.
+    fn grouping(expr: &Expr) -> String {
+        match expr {
+            Expr::Binary {
+                left,
+                operator,
+                right,
+            }
+            | Expr::Logical {
+                left,
+                operator,
+                right,
+            } => format!(
+                "({} {} {})",
+                operator.lexeme,
+                grouping(left),
+                grouping(right)
+            ),
+            Expr::Unary { operator, right } => {
+                format!("({} {})", operator.lexeme, grouping(right))
+            }
+            Expr::Variable { name, .. } => name.lexeme.to_string(),
+            other => panic!("unexpected expression {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_logical_operator_precedence() {
+        let cases = [
+            ("a or b and c;", "(or a (and b c))"),
+            ("a and b or c;", "(or (and a b) c)"),
+            ("not a == b;", "(not (== a b))"),
+            ("not a < b;", "(not (< a b))"),
+            ("not a and b;", "(and (not a) b)"),
+            ("a or not b;", "(or a (not b))"),
+            ("not not a;", "(not (not a))"),
+            ("!a == b;", "(== (! a) b)"),
+        ];
+        for (source, expected) in cases {
+            let statements = Parser::new(Lexer::lex(source)).parse().unwrap();
+            if let Expression(expr) = &statements[0] {
+                assert_eq!(grouping(expr), expected, "{}", source);
+            } else {
+                panic!("expected an expression statement for {}", source);
+            }
+        }
+    }
 }
@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 
-use crate::expr::{Expr, ExprVisitor};
+use crate::expr::{Expr, ExprVisitor, VarRef};
 use crate::stmt::{Stmt, StmtVisitor};
 use crate::token::{Literal, Token};
 use crate::token_type::TokenType;
@@ -10,7 +10,7 @@ use crate::token_type::TokenType;
 pub enum ResolverError {
     NoScope,
     DuplicateVariable(String),
-    NotFound(String),
+    NotFound { name: String, line: usize },
     SyntaxError(String),
 }
 
@@ -22,18 +22,59 @@ impl std::fmt::Display for ResolverError {
             ResolverError::DuplicateVariable(name) => {
                 write!(f, "Duplicate variable '{}'", name)
             }
-            ResolverError::NotFound(name) => write!(f, "Not found '{}'", name),
+            ResolverError::NotFound { name, line } => {
+                write!(f, "[line {}] Error at '{}': Undefined variable.", line, name)
+            }
             ResolverError::SyntaxError(msg) => write!(f, "{}", msg),
         }
     }
 }
 
+/// Problems that don't stop a program from running but are likely mistakes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResolverWarning {
+    ShadowedVariable { name: String, line: usize },
+    UnreachableCode { keyword: String, line: usize },
+}
+
+impl std::fmt::Display for ResolverWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ResolverWarning::ShadowedVariable { name, line } => write!(
+                f,
+                "[line {}] Warning: Variable '{}' shadows a variable in an enclosing scope.",
+                line, name
+            ),
+            ResolverWarning::UnreachableCode { keyword, line } => write!(
+                f,
+                "[line {}] Warning: Code after '{}' is unreachable.",
+                line, keyword
+            ),
+        }
+    }
+}
+
 pub struct Resolver {
     scopes: Vec<Vec<(Token, bool)>>,
     current_function: FunctionType,
     current_class: ClassType,
-    //resolved_data: HashMap<(String, usize), (usize, usize)>, 
-    resolved_data: HashMap<Token, (usize, usize)>,
+    // how many loops enclose the code being resolved within the current function
+    loop_depth: usize,
+    resolved_data: HashMap<VarRef, (usize, usize)>,
+    warnings: Vec<ResolverWarning>,
+    globals: HashSet<String>,
+    global_declarations: HashMap<String, Token>,
+    declarations: HashMap<Token, Token>,
+    // declaration tokens of constants, and the names assigned to anywhere in the program
+    consts: HashSet<Token>,
+    assignments: Vec<Token>,
+    // names that weren't found in any scope; checked against `globals` once the whole
+    // program has been seen so that globals can be referenced before they're declared
+    unresolved: Vec<Token>,
+    // the superclass named by each class declared in an open scope, keyed by the depth of
+    // that scope and the class name, so that inheritance cycles can be spotted; the flag is
+    // set once the superclass name is bound to a declaration the class actually extends
+    superclasses: HashMap<(usize, String), (Token, bool)>,
 }
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
 
@@ -57,10 +98,75 @@ impl Resolver {
             scopes: vec![],
             current_function: FunctionType::None,
             current_class: ClassType::None,
+            loop_depth: 0,
             resolved_data: Default::default(),
+            warnings: vec![],
+            globals: Default::default(),
+            global_declarations: Default::default(),
+            declarations: Default::default(),
+            consts: Default::default(),
+            assignments: vec![],
+            unresolved: vec![],
+            superclasses: Default::default(),
+        }
+    }
+
+    /// Registers names that are already defined in the global scope, such as natives or
+    /// globals from earlier runs on the same interpreter.
+    pub fn declare_globals<I: IntoIterator<Item = String>>(&mut self, names: I) {
+        self.globals.extend(names);
+    }
+
+    pub fn warnings(&self) -> &[ResolverWarning] {
+        &self.warnings
+    }
+
+    /// Maps each resolved variable use to the token that declared it, for tooling such as
+    /// go-to-definition. Uses of natives and of globals from earlier runs aren't included.
+    pub fn declarations(&self) -> &HashMap<Token, Token> {
+        &self.declarations
+    }
+
+    fn check_shadowing(&mut self, name: &Token) {
+        let Some((_, enclosing)) = self.scopes.split_last() else {
+            return;
+        };
+        let shadows = enclosing
+            .iter()
+            .any(|scope| scope.iter().any(|(token, _)| token.lexeme == name.lexeme));
+        if shadows {
+            self.warnings.push(ResolverWarning::ShadowedVariable {
+                name: name.lexeme.clone(),
+                line: name.line,
+            });
+        }
+    }
+
+    /// Warns once if any statement follows one that always leaves the block.
+    fn check_unreachable(&mut self, statements: &[Stmt]) {
+        let Some((_, leading)) = statements.split_last() else {
+            return;
+        };
+        let exit = leading.iter().find_map(|stmt| match stmt {
+            Stmt::Return { keyword, .. } | Stmt::Continue { keyword } => Some(keyword),
+            _ => None,
+        });
+        if let Some(keyword) = exit {
+            self.warnings.push(ResolverWarning::UnreachableCode {
+                keyword: keyword.lexeme.clone(),
+                line: keyword.line,
+            });
         }
     }
 
+    fn resolve_loop_body(&mut self, body: &Stmt) -> Result<(), ResolverError> {
+        let enclosing_loop_depth = self.loop_depth;
+        self.loop_depth += 1;
+        let result = self.resolve_stmt(body.clone());
+        self.loop_depth = enclosing_loop_depth;
+        result
+    }
+
     pub fn begin_scope(&mut self) {
         self.scopes.push(Vec::new());
     }
@@ -68,27 +174,61 @@ impl Resolver {
     pub fn resolve(
         &mut self,
         statements: &Vec<Stmt>,
-    ) -> Result<HashMap<Token, (usize, usize)>, ResolverError> {
+    ) -> Result<HashMap<VarRef, (usize, usize)>, ResolverError> {
+        let is_program = self.scopes.is_empty();
+        self.check_unreachable(statements);
         for stmt in statements {
             self.resolve_stmt(stmt.clone())?;
         }
+        if is_program {
+            let undefined = self
+                .unresolved
+                .iter()
+                .find(|name| !self.globals.contains(&name.lexeme));
+            if let Some(name) = undefined {
+                return Err(ResolverError::NotFound {
+                    name: name.lexeme.clone(),
+                    line: name.line,
+                });
+            }
+            // names used before any declaration of them refer to the last one
+            for name in &self.unresolved {
+                if let Some(declaration) = self.global_declarations.get(&name.lexeme) {
+                    self.declarations
+                        .entry(name.clone())
+                        .or_insert_with(|| declaration.clone());
+                }
+            }
+            let const_assignment = self.assignments.iter().find(|name| {
+                self.declarations
+                    .get(name)
+                    .is_some_and(|declaration| self.consts.contains(declaration))
+            });
+            if let Some(name) = const_assignment {
+                return Err(ResolverError::SyntaxError(format!(
+                    "[line {}] Error at '{}': cannot assign to const {}",
+                    name.line, name.lexeme, name.lexeme
+                )));
+            }
+        }
         Ok(self.resolved_data.clone())
     }
 
-    pub fn resolve_local(&mut self, _expr: Expr, name: Token) -> Result<(), ResolverError> {
-        for (dist_index, scope) in self.scopes.iter_mut().rev().enumerate() {
-            let mut found = false;
-            for idx in 0..scope.len() {
-                let val = scope.get_mut(idx);
-                if val.as_ref().unwrap().0.lexeme == name.lexeme.as_str() {
-                    self.resolved_data.insert(name.clone(), (dist_index, idx));
-                    found = true;
-                }
-            }
-            if found {
-                break;
+    pub fn resolve_local(&mut self, var: VarRef, name: Token) -> Result<(), ResolverError> {
+        // a scope holds a name at most once, so the innermost scope declaring it wins
+        for (dist_index, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(idx) = scope.iter().position(|(token, _)| token.lexeme == name.lexeme) {
+                self.resolved_data.insert(var, (dist_index, idx));
+                self.declarations.insert(name, scope[idx].0.clone());
+                return Ok(());
             }
         }
+        // a global that has been declared by this point is the one the name refers to here;
+        // later redeclarations don't change that
+        if let Some(declaration) = self.global_declarations.get(&name.lexeme) {
+            self.declarations.insert(name.clone(), declaration.clone());
+        }
+        self.unresolved.push(name);
         Ok(())
     }
 
@@ -102,13 +242,76 @@ impl Resolver {
 
     pub fn end_scope(&mut self) {
         self.scopes.pop();
+        let depth = self.scopes.len();
+        self.superclasses.retain(|(scope, _), _| *scope <= depth);
+    }
+
+    /// Records the superclass of a class declared in the current scope, rejecting the class
+    /// if its chain of superclasses declared in the same scope leads back to it, e.g.
+    /// `class A: B {}` followed by `class B: A {}`. Classes that extend an earlier class of
+    /// the same name are unaffected by its redeclaration.
+    fn record_class(
+        &mut self,
+        class: &Token,
+        superclass: Option<&Token>,
+    ) -> Result<(), ResolverError> {
+        let depth = self.scopes.len();
+        // links bound to the class this one replaces no longer lead anywhere, and links that
+        // named it before it was declared now extend this declaration
+        self.superclasses.retain(|(scope, _), (parent, bound)| {
+            *scope != depth || parent.lexeme != class.lexeme || !*bound
+        });
+        for ((scope, _), (parent, bound)) in self.superclasses.iter_mut() {
+            if *scope == depth && parent.lexeme == class.lexeme {
+                *bound = true;
+            }
+        }
+        self.superclasses.remove(&(depth, class.lexeme.clone()));
+
+        let Some(superclass) = superclass else {
+            return Ok(());
+        };
+        let mut chain = vec![class.lexeme.clone(), superclass.lexeme.clone()];
+        let mut ancestor = superclass;
+        while let Some((next, _)) = self.superclasses.get(&(depth, ancestor.lexeme.clone())) {
+            chain.push(next.lexeme.clone());
+            if next.lexeme == class.lexeme {
+                return Err(ResolverError::SyntaxError(format!(
+                    "[line {}] Error at '{}': Class '{}' cannot inherit from itself ({}).",
+                    superclass.line,
+                    superclass.lexeme,
+                    class.lexeme,
+                    chain.join(" -> ")
+                )));
+            }
+            ancestor = next;
+        }
+        let bound = self.is_declared(superclass);
+        self.superclasses
+            .insert((depth, class.lexeme.clone()), (superclass.clone(), bound));
+        Ok(())
+    }
+
+    // whether `name` refers to a declaration that has already been seen
+    fn is_declared(&self, name: &Token) -> bool {
+        self.globals.contains(&name.lexeme)
+            || self
+                .scopes
+                .iter()
+                .any(|scope| scope.iter().any(|(token, _)| token.lexeme == name.lexeme))
     }
 
     pub fn declare(&mut self, name: Token) -> Result<(), ResolverError> {
         if self.scopes.is_empty() {
+            // a redeclared global replaces the earlier binding, constness included
+            self.globals.insert(name.lexeme.clone());
+            self.global_declarations.insert(name.lexeme.clone(), name);
             return Ok(());
         }
         let scope = self.scopes.last_mut().unwrap(); // Handle potential None case if needed
+        if scope.iter().any(|(token, _)| token.lexeme == name.lexeme) {
+            return Err(ResolverError::DuplicateVariable(name.lexeme));
+        }
         scope.push((name, false));
         Ok(())
     }
@@ -130,9 +333,20 @@ impl Resolver {
         stmt: Stmt,
         func_type: FunctionType,
     ) -> Result<(), ResolverError> {
-        if let Stmt::Function {  params, body, .. } = stmt {
+        if let Stmt::Function {
+            params,
+            defaults,
+            body,
+            ..
+        } = stmt
+        {
+            // defaults are evaluated in the function's closure environment at call time
+            for default in defaults.iter().flatten() {
+                self.resolve_expr(default)?;
+            }
             let enclosing_function = self.current_function.clone();
             self.current_function = func_type;
+            let enclosing_loop_depth = std::mem::take(&mut self.loop_depth);
             self.begin_scope();
             for param in params.iter() {
                 self.declare(param.clone())?;
@@ -142,6 +356,7 @@ impl Resolver {
             self.end_scope();
 
             self.current_function = enclosing_function;
+            self.loop_depth = enclosing_loop_depth;
         }
         Ok(())
     }
@@ -158,19 +373,35 @@ impl StmtVisitor for &mut Resolver {
     }
 
     fn visit_print_stmt(&mut self, stmt: &Stmt) -> Self::T {
-        if let Stmt::Print(expr) = stmt {
-            self.resolve_expr(expr)?;
+        if let Stmt::Print(exprs) = stmt {
+            for expr in exprs {
+                self.resolve_expr(expr)?;
+            }
         }
         Ok(())
     }
 
     fn visit_decl_stmt(&mut self, stmt: &Stmt) -> Self::T {
         if let Stmt::Var { name, initializer } = stmt {
-            self.declare(name.clone())?;
+            // the initializer is resolved before the name is declared, so in
+            // `let x = x + 1;` it reads the `x` of an enclosing scope
             if let Some(init_val) = initializer {
                 self.resolve_expr(init_val)?;
             }
+            self.check_shadowing(name);
+            self.declare(name.clone())?;
+            self.define(name.clone())?;
+        }
+        Ok(())
+    }
+
+    fn visit_const_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Const { name, initializer } = stmt {
+            self.resolve_expr(initializer)?;
+            self.check_shadowing(name);
+            self.declare(name.clone())?;
             self.define(name.clone())?;
+            self.consts.insert(name.clone());
         }
         Ok(())
     }
@@ -203,7 +434,40 @@ impl StmtVisitor for &mut Resolver {
     fn visit_while_stmt(&mut self, stmt: &Stmt) -> Self::T {
         if let Stmt::While { condition, body } = stmt {
             self.resolve_expr(condition)?;
-            self.resolve_stmt(body.as_ref().clone())?;
+            self.resolve_loop_body(body)?;
+        }
+        Ok(())
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::For {
+            initializer,
+            condition,
+            increment,
+            body,
+        } = stmt
+        {
+            self.begin_scope();
+            if let Some(initializer) = initializer {
+                self.resolve_stmt(initializer.as_ref().clone())?;
+            }
+            if let Some(condition) = condition {
+                self.resolve_expr(condition)?;
+            }
+            if let Some(increment) = increment {
+                self.resolve_expr(increment)?;
+            }
+            self.resolve_loop_body(body)?;
+            self.end_scope();
+        }
+        Ok(())
+    }
+
+    fn visit_continue_stmt(&mut self, _stmt: &Stmt) -> Self::T {
+        if self.loop_depth == 0 {
+            return Err(ResolverError::SyntaxError(
+                "Continue not allowed outside of a loop.".into(),
+            ));
         }
         Ok(())
     }
@@ -223,17 +487,18 @@ impl StmtVisitor for &mut Resolver {
                 "Return not allowed at top-level code.".into(),
             ));
         }
-        if let Stmt::Return { value, .. } = stmt {
-            if value.is_some() {
-                
-                if self.current_function == FunctionType::Initializer  {
-                    
-                    return Err(ResolverError::SyntaxError(
-                        "Cannot return value from initializer.".into(),
-                    ));
-                }
-                self.resolve_expr(&value.clone().unwrap())?;
+        if let Stmt::Return {
+            keyword,
+            value: Some(value),
+        } = stmt
+        {
+            if self.current_function == FunctionType::Initializer {
+                return Err(ResolverError::SyntaxError(format!(
+                    "[line {}] Error at 'return': Can't return a value from an initializer.",
+                    keyword.line
+                )));
             }
+            self.resolve_expr(value)?;
         }
         Ok(())
     }
@@ -243,8 +508,22 @@ impl StmtVisitor for &mut Resolver {
             name,
             methods,
             superclass,
+            overrides,
+            getters,
         } = stmt
         {
+            if let (None, Some(method)) = (superclass, overrides.first()) {
+                return Err(ResolverError::SyntaxError(format!(
+                    "[line {}] Error at '{}': Can't override a method in a class with no superclass.",
+                    method.line, method.lexeme
+                )));
+            }
+            if let Some(init) = getters.iter().find(|getter| getter.lexeme == "init") {
+                return Err(ResolverError::SyntaxError(format!(
+                    "[line {}] Error at 'init': An initializer can't be a getter.",
+                    init.line
+                )));
+            }
             let enclosing_class = self.current_class;
             self.current_class = ClassType::Class;
 
@@ -252,16 +531,21 @@ impl StmtVisitor for &mut Resolver {
             self.define(name.clone())?;
 
             let class_name = name.clone();
+            let superclass_name = match superclass {
+                Some(Expr::Variable { name, .. }) => Some(name),
+                _ => None,
+            };
+            if let Some(name) = superclass_name {
+                if name.lexeme == class_name.lexeme {
+                    return Err(ResolverError::SyntaxError(format!(
+                        "[line {}] Error at '{}': A class cannot inherit from itself.",
+                        name.line, name.lexeme
+                    )));
+                }
+            }
+            self.record_class(&class_name, superclass_name)?;
             if let Some(sc) = superclass {
                 self.current_class = ClassType::SubClass;
-                if let Expr::Variable { name } = sc {
-                    if name.lexeme == class_name.lexeme {
-                        return Err(ResolverError::SyntaxError(
-                            format!("Error at '{}': A class cannot inherit from itself.", name.lexeme),
-                            
-                        ));
-                    }
-                }
                 self.resolve_expr(sc)?;
 
                 self.begin_scope();
@@ -294,14 +578,66 @@ impl StmtVisitor for &mut Resolver {
         }
         Ok(())
     }
+
+    fn visit_throw_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Throw { value, .. } = stmt {
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_try_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Try {
+            body,
+            catch_name,
+            catch_body,
+        } = stmt
+        {
+            self.begin_scope();
+            self.resolve(body)?;
+            self.end_scope();
+
+            self.begin_scope();
+            self.declare(catch_name.clone())?;
+            self.define(catch_name.clone())?;
+            self.resolve(catch_body)?;
+            self.end_scope();
+        }
+        Ok(())
+    }
+
+    fn visit_match_stmt(&mut self, stmt: &Stmt) -> Self::T {
+        if let Stmt::Match {
+            subject,
+            cases,
+            default,
+        } = stmt
+        {
+            self.resolve_expr(subject)?;
+            for (value, body) in cases.iter() {
+                self.resolve_expr(value)?;
+                self.begin_scope();
+                self.resolve(body)?;
+                self.end_scope();
+            }
+            if let Some(body) = default {
+                self.begin_scope();
+                self.resolve(body)?;
+                self.end_scope();
+            }
+        }
+        Ok(())
+    }
 }
+
 impl ExprVisitor for &mut Resolver {
     type T = Result<(), ResolverError>;
 
     fn visit_assign_expr(&mut self, expr: &Expr) -> Self::T {
-        if let Expr::Assign { name, value } = expr {
+        if let Expr::Assign { name, value, var } = expr {
             self.resolve_expr(value)?;
-            self.resolve_local(expr.clone(), name.clone())?;
+            self.resolve_local(*var, name.clone())?;
+            self.assignments.push(name.clone());
         }
         Ok(())
     }
@@ -310,6 +646,27 @@ impl ExprVisitor for &mut Resolver {
         Ok(())
     }
 
+    fn visit_list_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::List { elements } = expr {
+            for element in elements {
+                self.resolve_expr(element)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_block_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::Block { statements, value } = expr {
+            self.begin_scope();
+            self.resolve(statements)?;
+            if let Some(value) = value {
+                self.resolve_expr(value)?;
+            }
+            self.end_scope();
+        }
+        Ok(())
+    }
+
     fn visit_binary_expr(&mut self, expr: &Expr) -> Self::T {
         if let Expr::Binary {
             left,
@@ -354,7 +711,7 @@ impl ExprVisitor for &mut Resolver {
 
     fn visit_variable_expr(&mut self, expr: &Expr) -> Self::T {
         let mut ret_val = Ok(());
-        if let Expr::Variable { name } = expr {
+        if let Expr::Variable { name, var } = expr {
             if !self.scopes.is_empty()
                 && self.scopes.last().is_some()
                 && self
@@ -379,7 +736,7 @@ impl ExprVisitor for &mut Resolver {
                     name.lexeme
                 )))
             }
-            self.resolve_local(expr.clone(), name.clone())?;
+            self.resolve_local(*var, name.clone())?;
         }
 
         ret_val
@@ -416,20 +773,57 @@ impl ExprVisitor for &mut Resolver {
             ..
         } = expr
         {
-            self.resolve_expr(value)?;
+            // in evaluation order, so the first undefined name is the one reported
             self.resolve_expr(object)?;
+            self.resolve_expr(value)?;
         };
         Ok(())
     }
 
+    fn visit_index_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::Index { object, index, .. } = expr {
+            self.resolve_expr(object)?;
+            self.resolve_expr(index)?;
+        }
+        Ok(())
+    }
+
+    fn visit_slice_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::Slice {
+            object, start, end, ..
+        } = expr
+        {
+            self.resolve_expr(object)?;
+            for bound in [start, end].into_iter().flatten() {
+                self.resolve_expr(bound)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_set_index_expr(&mut self, expr: &Expr) -> Self::T {
+        if let Expr::SetIndex {
+            object,
+            index,
+            value,
+            ..
+        } = expr
+        {
+            self.resolve_expr(object)?;
+            self.resolve_expr(index)?;
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
     fn visit_this_expr(&mut self, expr: &Expr) -> Self::T {
-        let res = if let Expr::This { keyword } = expr {
+        let res = if let Expr::This { keyword, var } = expr {
             if self.current_class == ClassType::None {
                 Err(ResolverError::SyntaxError(
                     "Can't use 'this' outside of a class".into(),
                 ))
             } else {
-                self.resolve_local(expr.clone(), keyword.clone())?;
+                self.resolve_local(*var, keyword.clone())?;
                 Ok(())
             }
         } else {
@@ -441,7 +835,7 @@ impl ExprVisitor for &mut Resolver {
     }
 
     fn visit_super_expr(&mut self, expr: &Expr) -> Self::T {
-        if let Expr::Super { keyword, .. } = expr {
+        if let Expr::Super { keyword, var, .. } = expr {
             let res = if self.current_class == ClassType::None {
                 Err(ResolverError::SyntaxError(
                     "Can't use 'super' outside of a class".into(),
@@ -451,7 +845,7 @@ impl ExprVisitor for &mut Resolver {
                     "Can't use 'super' in a class with no superclass".into(),
                 ))
             } else {
-                self.resolve_local(expr.clone(), keyword.clone())?;
+                self.resolve_local(*var, keyword.clone())?;
                 Ok(())
             };
             res
@@ -462,3 +856,296 @@ impl ExprVisitor for &mut Resolver {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::expr::{Expr, VarRef};
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::resolver::{Resolver, ResolverError, ResolverWarning};
+    use crate::stmt::Stmt;
+
+    fn resolve(source: &str) -> Result<(), ResolverError> {
+        let tokens = Lexer::lex(source);
+        let ast = Parser::new(tokens).parse().unwrap();
+        Resolver::new().resolve(&ast).map(|_| ())
+    }
+
+    #[test]
+    fn test_undefined_local() {
+        let source = r#"
+def f() {
+    let a = 1;
+    return a + b;
+}"#;
+        let error = resolve(source).unwrap_err();
+        assert!(matches!(
+            error,
+            ResolverError::NotFound { ref name, line: 4 } if name == "b"
+        ));
+    }
+
+    #[test]
+    fn test_continue_outside_of_a_loop() {
+        assert!(resolve("while (true) { continue; }").is_ok());
+        assert!(resolve("for (;;) { if (true) continue; }").is_ok());
+        for source in [
+            "continue;",
+            "while (true) { def f() { continue; } }",
+        ] {
+            assert!(matches!(
+                resolve(source),
+                Err(ResolverError::SyntaxError(ref msg)) if msg == "Continue not allowed outside of a loop."
+            ));
+        }
+    }
+
+    #[test]
+    fn test_inheritance_cycle() {
+        let error = |source: &str| match resolve(source) {
+            Err(ResolverError::SyntaxError(msg)) => msg,
+            other => panic!("expected a syntax error, got {:?}", other.map(|_| ())),
+        };
+        assert_eq!(
+            error("class A: B {} class B: A {}"),
+            "[line 1] Error at 'A': Class 'B' cannot inherit from itself (B -> A -> B)."
+        );
+        assert_eq!(
+            error("class A: C {} class B: A {} class C: B {}"),
+            "[line 1] Error at 'B': Class 'C' cannot inherit from itself (C -> B -> A -> C)."
+        );
+        assert_eq!(
+            error("def f() { class A: B {} class B: A {} }"),
+            "[line 1] Error at 'A': Class 'B' cannot inherit from itself (B -> A -> B)."
+        );
+
+        // classes with the same names in separate scopes are unrelated
+        assert!(resolve("class B {} class A: B {} { class B: A {} }").is_ok());
+        // A extends the first B, which the second one doesn't replace as its superclass
+        assert!(resolve("class B {} class A: B {} class B: A {}").is_ok());
+        assert!(resolve("class B {} { class A: B {} class B: A {} }").is_ok());
+    }
+
+    #[test]
+    fn test_forward_referenced_global() {
+        let source = r#"
+def is_even(n) {
+    if (n == 0) return true;
+    return is_odd(n - 1);
+}
+def is_odd(n) {
+    if (n == 0) return false;
+    return is_even(n - 1);
+}"#;
+        assert!(resolve(source).is_ok());
+    }
+
+    #[test]
+    fn test_code_after_return_warns_once() {
+        let source = r#"
+def f() {
+    return 1;
+    print "unreachable";
+    print "also unreachable";
+}"#;
+        let tokens = Lexer::lex(source);
+        let ast = Parser::new(tokens).parse().unwrap();
+        let mut resolver = Resolver::new();
+        resolver.resolve(&ast).unwrap();
+        assert_eq!(
+            resolver.warnings(),
+            &[ResolverWarning::UnreachableCode {
+                keyword: "return".to_string(),
+                line: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_declarations_map_uses_to_declarations() {
+        let source = r#"
+let greeting = "hi";
+def greet(name) {
+    let greeting = "hello";
+    print greeting + name;
+}
+print greeting;"#;
+        let tokens = Lexer::lex(source);
+        let ast = Parser::new(tokens).parse().unwrap();
+        let mut resolver = Resolver::new();
+        resolver.resolve(&ast).unwrap();
+
+        let declaration_line = |lexeme: &str, use_line: usize| {
+            resolver
+                .declarations()
+                .iter()
+                .find(|(usage, _)| usage.lexeme == lexeme && usage.line == use_line)
+                .map(|(_, declaration)| declaration.line)
+        };
+        assert_eq!(declaration_line("greeting", 5), Some(4));
+        assert_eq!(declaration_line("name", 5), Some(3));
+        assert_eq!(declaration_line("greeting", 7), Some(2));
+    }
+
+    #[test]
+    fn test_resolutions_are_keyed_by_variable_reference() {
+        // each function reads its parameter three times and assigns it once; uses of the
+        // global function names stay unresolved
+        let source = (0..100)
+            .map(|n| format!("def f{n}(a) {{ a = a + a; return a; }}\nprint f{n}(1);\n"))
+            .collect::<String>();
+        let ast = Parser::new(Lexer::lex(&source)).parse().unwrap();
+        let resolved = Resolver::new().resolve(&ast).unwrap();
+        assert_eq!(resolved.len(), 100 * 4);
+        assert!(resolved.values().all(|resolution| *resolution == (0, 0)));
+        assert_eq!(std::mem::size_of::<VarRef>(), 4);
+    }
+
+    #[test]
+    fn test_initializer_reads_the_shadowed_variable() {
+        let source = r#"
+{
+    let x = 1;
+    {
+        let x = x + 1;
+        print x;
+    }
+}"#;
+        let tokens = Lexer::lex(source);
+        let ast = Parser::new(tokens).parse().unwrap();
+        let mut resolver = Resolver::new();
+        resolver.resolve(&ast).unwrap();
+
+        let declaration_line = |use_line: usize| {
+            resolver
+                .declarations()
+                .iter()
+                .find(|(usage, _)| usage.lexeme == "x" && usage.line == use_line)
+                .map(|(_, declaration)| declaration.line)
+        };
+        assert_eq!(declaration_line(5), Some(3));
+        assert_eq!(declaration_line(6), Some(5));
+    }
+
+    #[test]
+    fn test_super_and_this_resolve_by_reference() {
+        let source = r#"
+class A { m() { return 1; } }
+class B: A {
+    m() {
+        return super.m() + this.n;
+    }
+}"#;
+        let ast = Parser::new(Lexer::lex(source)).parse().unwrap();
+        let resolved = Resolver::new().resolve(&ast).unwrap();
+
+        let Stmt::Class { methods, .. } = &ast[1] else {
+            panic!("expected a class statement");
+        };
+        let Stmt::Function { body, .. } = &methods[0] else {
+            panic!("expected a method");
+        };
+        let Stmt::Return {
+            value: Some(Expr::Binary { left, right, .. }),
+            ..
+        } = &body[0]
+        else {
+            panic!("expected a return statement");
+        };
+        let Expr::Call { callee, .. } = left.as_ref() else {
+            panic!("expected a call");
+        };
+        let (Expr::Super { var: super_var, .. }, Expr::Get { object, .. }) =
+            (callee.as_ref(), right.as_ref())
+        else {
+            panic!("expected super and this expressions");
+        };
+        let Expr::This { var: this_var, .. } = object.as_ref() else {
+            panic!("expected a this expression");
+        };
+        // `this` is bound one namespace inside the one binding `super`
+        let (super_distance, _) = resolved[super_var];
+        let (this_distance, _) = resolved[this_var];
+        assert_eq!(super_distance, this_distance + 1);
+    }
+
+    #[test]
+    fn test_duplicate_parameters() {
+        let error = resolve("def f(a, a) {}").unwrap_err();
+        assert!(matches!(error, ResolverError::DuplicateVariable(ref name) if name == "a"));
+    }
+
+    #[test]
+    fn test_duplicate_local_declaration() {
+        let source = r#"
+{
+    let a = 1;
+    let a = 2;
+}"#;
+        let error = resolve(source).unwrap_err();
+        assert!(matches!(error, ResolverError::DuplicateVariable(ref name) if name == "a"));
+
+        // globals may be redefined
+        assert!(resolve("let a = 1; let a = 2;").is_ok());
+    }
+
+    #[test]
+    fn test_const_can_be_read() {
+        let source = r#"
+const limit = 10;
+def under_limit(n) {
+    const margin = 1;
+    return n + margin < limit;
+}"#;
+        assert!(resolve(source).is_ok());
+    }
+
+    #[test]
+    fn test_const_cannot_be_reassigned() {
+        let error = resolve("const limit = 10;\nlimit = 11;").unwrap_err();
+        assert!(matches!(
+            error,
+            ResolverError::SyntaxError(ref msg)
+                if msg == "[line 2] Error at 'limit': cannot assign to const limit"
+        ));
+
+        let source = r#"
+def f() {
+    const margin = 1;
+    margin = 2;
+}"#;
+        assert!(resolve(source).is_err());
+    }
+
+    #[test]
+    fn test_const_follows_the_global_declaration_in_effect() {
+        assert!(resolve("const x = 1;\nlet x = 2;\nx = 3;").is_ok());
+
+        let error = resolve("let y = 1;\nconst y = 2;\ny = 3;\nprint y;").unwrap_err();
+        assert!(matches!(
+            error,
+            ResolverError::SyntaxError(ref msg)
+                if msg == "[line 3] Error at 'y': cannot assign to const y"
+        ));
+
+        // the const is the declaration in effect when it's assigned, whatever comes later
+        let error = resolve("const y = 2;\ny = 3;\nlet y = 4;").unwrap_err();
+        assert!(matches!(
+            error,
+            ResolverError::SyntaxError(ref msg)
+                if msg == "[line 2] Error at 'y': cannot assign to const y"
+        ));
+    }
+
+    #[test]
+    fn test_override_requires_superclass() {
+        let source = "class Base { area() {} }\nclass Square: Base { override area() {} }";
+        assert!(resolve(source).is_ok());
+
+        let error = resolve("class Shape {\n  override area() {}\n}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "[line 2] Error at 'area': Can't override a method in a class with no superclass."
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use crate::expr::Expr;
 use crate::token::Token;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Stmt {
     Expression(Expr),
     If {
@@ -9,7 +9,8 @@ pub enum Stmt {
         then_branch: Box<Stmt>,
         else_branch: Box<Option<Stmt>>,
     },
-    Print(Expr),
+    /// Prints its values separated by spaces, followed by a newline.
+    Print(Vec<Expr>),
     Return {
         keyword: Token,
         value: Option<Expr>,
@@ -18,20 +19,58 @@ pub enum Stmt {
         name: Token,
         initializer: Option<Expr>,
     },
+    Const {
+        name: Token,
+        initializer: Expr,
+    },
     While {
         condition: Expr,
         body: Box<Stmt>,
     },
+    /// A C-style `for` loop. The initializer lives in a scope of its own that the other
+    /// clauses and the body share; a missing condition loops forever.
+    For {
+        initializer: Option<Box<Stmt>>,
+        condition: Option<Expr>,
+        increment: Option<Expr>,
+        body: Box<Stmt>,
+    },
+    Continue {
+        keyword: Token,
+    },
     Block(Vec<Stmt>),
     Function {
         name: Token,
         params: Vec<Token>,
+        defaults: Vec<Option<Expr>>,
         body: Vec<Stmt>,
     },
     Class {
         name: Token,
         superclass: Option<Expr>,
         methods: Vec<Stmt>,
+        /// Names of the methods declared with `override`, which must replace a method of
+        /// the superclass.
+        overrides: Vec<Token>,
+        /// Names of the methods declared with `get`, which are called when they are read as
+        /// a property instead of returning a bound method.
+        getters: Vec<Token>,
+    },
+    Throw {
+        keyword: Token,
+        value: Expr,
+    },
+    Try {
+        body: Vec<Stmt>,
+        catch_name: Token,
+        catch_body: Vec<Stmt>,
+    },
+    /// Runs the body of the first case whose value equals the subject, or the default body
+    /// when none does. Cases don't fall through to the next one.
+    Match {
+        subject: Expr,
+        cases: Vec<(Expr, Vec<Stmt>)>,
+        default: Option<Vec<Stmt>>,
     },
 }
 
@@ -44,12 +83,18 @@ impl Stmt {
                 name: _,
                 initializer: _,
             } => visitor.visit_decl_stmt(self),
+            Stmt::Const { .. } => visitor.visit_const_stmt(self),
             Stmt::Block(_v) => visitor.visit_block_stmt(self),
             Stmt::If { .. } => visitor.visit_if_stmt(self),
             Stmt::While { .. } => visitor.visit_while_stmt(self),
+            Stmt::For { .. } => visitor.visit_for_stmt(self),
+            Stmt::Continue { .. } => visitor.visit_continue_stmt(self),
             Stmt::Function { .. } => visitor.visit_function_stmt(self),
             Stmt::Return { .. } => visitor.visit_return_stmt(self),
             Stmt::Class { .. } => visitor.visit_class_stmt(self),
+            Stmt::Throw { .. } => visitor.visit_throw_stmt(self),
+            Stmt::Try { .. } => visitor.visit_try_stmt(self),
+            Stmt::Match { .. } => visitor.visit_match_stmt(self),
         }
     }
 }
@@ -60,11 +105,17 @@ pub trait StmtVisitor {
     fn visit_expression_stmt(&mut self, stmt: &Stmt) -> Self::T;
     fn visit_print_stmt(&mut self, stmt: &Stmt) -> Self::T;
     fn visit_decl_stmt(&mut self, stmt: &Stmt) -> Self::T;
+    fn visit_const_stmt(&mut self, stmt: &Stmt) -> Self::T;
     fn visit_block_stmt(&mut self, stmt: &Stmt) -> Self::T;
     fn visit_if_stmt(&mut self, stmt: &Stmt) -> Self::T;
     fn visit_while_stmt(&mut self, stmt: &Stmt) -> Self::T;
+    fn visit_for_stmt(&mut self, stmt: &Stmt) -> Self::T;
+    fn visit_continue_stmt(&mut self, stmt: &Stmt) -> Self::T;
     fn visit_function_stmt(&mut self, stmt: &Stmt) -> Self::T;
     //
     fn visit_return_stmt(&mut self, stmt: &Stmt) -> Self::T;
     fn visit_class_stmt(&mut self, stmt: &Stmt) -> Self::T;
+    fn visit_throw_stmt(&mut self, stmt: &Stmt) -> Self::T;
+    fn visit_try_stmt(&mut self, stmt: &Stmt) -> Self::T;
+    fn visit_match_stmt(&mut self, stmt: &Stmt) -> Self::T;
 }
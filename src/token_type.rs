@@ -16,17 +16,22 @@ pub enum TokenType {
     Slash,
     Dot,
     Rem,
+    Ampersand,
+    Pipe,
+    Caret,
 
     // One or two character token
     Less,
     Greater,
     Equal,
     EqualEqual,
-    NotEqual,
     LessEqual,
     GreaterEqual,
+    LessLess,
+    GreaterGreater,
     Bang,
     BangEqual,
+    QuestionQuestion,
 
     // Literals
     Identifier,
@@ -40,6 +45,7 @@ pub enum TokenType {
     False,
     For,
     If,
+    Not,
     Or,
     Return,
     Super,
@@ -48,16 +54,34 @@ pub enum TokenType {
     Def,
     This,
     Let,
+    Const,
     None,
     Print,
+    Throw,
+    Try,
+    Catch,
+    Continue,
+    Match,
+    Case,
+    Default,
+    Is,
 
     Newline,
     Whitespace,
     Indent,
     Dedent,
     Comment,
-    CommentMarker,
+    /// A `///` comment documenting the declaration that follows it.
+    DocComment,
 
     Error,
     EOF,
 }
+
+/// Tokens the lexer produces but the parser steps over.
+pub static TO_IGNORE: &'static [TokenType] = &[
+    TokenType::Comment,
+    TokenType::DocComment,
+    TokenType::Whitespace,
+    TokenType::Newline,
+];
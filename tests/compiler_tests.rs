@@ -13,11 +13,11 @@ lazy_static::lazy_static! {
     static ref ERROR_LINE_PATTERN: Regex = Regex::new(r"// \[((java|c) )?line (\d+)\] (Error.*)").unwrap();
     static ref EXPECTED_RUNTIME_ERROR_PATTERN: Regex = Regex::new(r"// expect runtime error: (.+)").unwrap();
     static ref SYNTAX_ERROR_PATTERN: Regex = Regex::new(r"\[.*line (\d+)\] (Error.+)").unwrap();
-    static ref STACK_TRACE_PATTERN: Regex = Regex::new(r"\[line (\d+)\]").unwrap();
+    static ref STACK_TRACE_PATTERN: Regex = Regex::new(r"^\[line (\d+)\] in ").unwrap();
     static ref NON_TEST_PATTERN: Regex = Regex::new(r"// nontest").unwrap();
 }
 
-static ALL_TEST_SUITES: [&str; 17] = [
+static ALL_TEST_SUITES: [&str; 27] = [
     "assignment",
     "block",
     "bool",
@@ -33,9 +33,18 @@ static ALL_TEST_SUITES: [&str; 17] = [
     "while",
     "closure",
     "comments",
-    "constructors",
+    "constructor",
     "logical_operator",
-    
+    "try",
+    "errors",
+    "list",
+    "native",
+    "string",
+    "super",
+    "match",
+    "inheritance",
+    "field",
+    "method",
 ];
 
 static TEST_SUITES: [&str; 0] = [];
@@ -94,10 +103,12 @@ fn test_compiler() {
         let output_strs = output
             .split("\n")
             .filter(|v| *v != "")
+            // the traceback under a runtime error is not part of what a test expects
+            .filter(|v| !STACK_TRACE_PATTERN.is_match(v))
             .map(|v| v.to_string())
             .collect::<Vec<String>>();
         let failures = validate_outputs(&expected_outputs, &output_strs);
-        println!("failures are {:?}",  failures);
+        println!("{test_path}: failures are {:?}", failures);
         test_results.push(failures.is_empty())
     }
     let mut res_df: DataFrame = df!(
@@ -130,5 +141,12 @@ fn validate_outputs<T: ToString + PartialEq>(
             failures.push((expected_output.to_string(), output.to_string()));
         }
     }
+    // output that never came, or that no expectation accounts for, fails the test too
+    for expected_output in expected_outputs.iter().skip(outputs.len()) {
+        failures.push((expected_output.to_string(), String::new()));
+    }
+    for output in outputs.iter().skip(expected_outputs.len()) {
+        failures.push((String::new(), output.to_string()));
+    }
     failures
 }